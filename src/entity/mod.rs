@@ -1,7 +1,5 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
-pub mod prelude;
-
 pub mod account;
 pub mod account_summoner;
 pub mod game_data;
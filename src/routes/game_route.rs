@@ -1,10 +1,12 @@
 use std::time::Instant;
 
 use crate::entity::{game_data, games};
-use crate::services::game_service::calculate;
-use crate::structs::game_struct::GameProps;
+use crate::services::game_service::{calculate, champion_report, damage_matrix, sandbox, what_if};
+use crate::structs::game_struct::{GameProps, GameSandboxRequest};
 use crate::structs::routes_struct::{
     HTTPErrorResponse, LastByCodeRequest, LastByCodeResponse, LastByCodeResponseData,
+    MatrixByCodeRequest, MatrixByCodeResponse, MatrixByCodeResponseData, ReportResponse,
+    SandboxResponse,
 };
 use actix_web::{web, HttpResponse, Responder};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
@@ -12,26 +14,26 @@ use sea_orm::{DatabaseConnection, QueryOrder};
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.route("/last", web::post().to(last_by_code));
+    cfg.route("/matrix", web::post().to(matrix_by_code));
+    cfg.route("/sandbox", web::post().to(sandbox_build));
+    cfg.route("/report/{champion}", web::get().to(champion_data_report));
 }
 
-pub async fn last_by_code(
-    db: web::Data<DatabaseConnection>,
-    body: Result<web::Json<LastByCodeRequest>, actix_web::Error>,
-) -> impl Responder {
-    let data = match body {
-        Ok(data) => data.into_inner(),
-        Err(_) => {
-            return HttpResponse::BadRequest().json(HTTPErrorResponse {
-                success: false,
-                message: "Invalid request: Verify any missing fields",
-            });
-        }
-    };
+fn bad_request() -> HttpResponse {
+    HttpResponse::BadRequest().json(HTTPErrorResponse {
+        success: false,
+        message: "Invalid request: Verify any missing fields",
+    })
+}
 
+async fn last_game(
+    db: &DatabaseConnection,
+    code: String,
+) -> Result<(games::Model, GameProps), HttpResponse> {
     let first_query = games::Entity::find()
-        .filter(games::Column::GameCode.eq(data.code))
+        .filter(games::Column::GameCode.eq(code))
         .order_by_desc(games::Column::CreatedAt)
-        .one(db.get_ref())
+        .one(db)
         .await;
 
     match first_query {
@@ -39,67 +41,159 @@ pub async fn last_by_code(
             let second_query = game_data::Entity::find()
                 .filter(game_data::Column::GameId.eq(query_1.game_id.clone()))
                 .order_by_desc(game_data::Column::GameTime)
-                .one(db.get_ref())
+                .one(db)
                 .await;
 
             match second_query {
                 Ok(Some(query_2)) => match serde_json::from_str::<GameProps>(&query_2.game_data) {
-                    Ok(game_props) => {
-                        let instant = Instant::now();
-                        let calc = calculate(game_props, &data.item).await;
-                        let elapsed = instant.elapsed();
-                        println!("Elapsed: {:.7?}", elapsed);
-                        let game = match serde_json::to_string(&calc) {
-                            Ok(json) => json,
-                            Err(_) => {
-                                return HttpResponse::InternalServerError()
-                                    .json("Failed to serialize game data");
-                            }
-                        };
-                        return HttpResponse::Ok().json(LastByCodeResponse {
-                            success: true,
-                            data: LastByCodeResponseData {
-                                game_id: query_1.game_id,
-                                summoner_name: query_1.summoner_name,
-                                created_at: query_1.created_at,
-                                game_code: query_1.game_code,
-                                champion_name: query_1.champion_name,
-                                game,
-                            },
-                        });
-                    }
-                    Err(_) => {
-                        return HttpResponse::InternalServerError().json(HTTPErrorResponse {
-                            success: false,
-                            message: "Failed to deserialize game data",
-                        });
-                    }
-                },
-                Ok(None) => {
-                    return HttpResponse::NotFound().json(HTTPErrorResponse {
-                        success: false,
-                        message: "No game data found with the provided code",
-                    });
-                }
-                Err(_) => {
-                    return HttpResponse::InternalServerError().json(HTTPErrorResponse {
+                    Ok(game_props) => Ok((query_1, game_props)),
+                    Err(_) => Err(HttpResponse::InternalServerError().json(HTTPErrorResponse {
                         success: false,
-                        message: "Failed to retrieve game data from the database",
-                    });
-                }
+                        message: "Failed to deserialize game data",
+                    })),
+                },
+                Ok(None) => Err(HttpResponse::NotFound().json(HTTPErrorResponse {
+                    success: false,
+                    message: "No game data found with the provided code",
+                })),
+                Err(_) => Err(HttpResponse::InternalServerError().json(HTTPErrorResponse {
+                    success: false,
+                    message: "Failed to retrieve game data from the database",
+                })),
             }
         }
-        Ok(None) => {
-            return HttpResponse::NotFound().json(HTTPErrorResponse {
+        Ok(None) => Err(HttpResponse::NotFound().json(HTTPErrorResponse {
+            success: false,
+            message: "No game found with the provided code",
+        })),
+        Err(_) => Err(HttpResponse::InternalServerError().json(HTTPErrorResponse {
+            success: false,
+            message: "Failed to retrieve game from the database",
+        })),
+    }
+}
+
+pub async fn last_by_code(
+    db: web::Data<DatabaseConnection>,
+    body: Result<web::Json<LastByCodeRequest>, actix_web::Error>,
+) -> impl Responder {
+    let data = match body {
+        Ok(data) => data.into_inner(),
+        Err(_) => return bad_request(),
+    };
+
+    if !data.options.overrides.as_ref().is_none_or(|o| o.is_valid()) {
+        return HttpResponse::BadRequest().json(HTTPErrorResponse {
+            success: false,
+            message: "Invalid overrides: level must be between 1 and 18",
+        });
+    }
+
+    let (query_1, game_props) = match last_game(db.get_ref(), data.code).await {
+        Ok(result) => result,
+        Err(response) => return response,
+    };
+
+    let instant = Instant::now();
+    let calc = match data.options.overrides.as_ref() {
+        Some(overrides) => what_if(game_props, &data.item, &data.options, overrides).await,
+        None => calculate(game_props, &data.item, &data.options).await,
+    };
+    let calc = match calc {
+        Ok(calc) => calc,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(HTTPErrorResponse {
                 success: false,
-                message: "No game found with the provided code",
+                message:
+                    "Invalid request: unsupported champion, unknown item or invalid ability ranks",
             });
         }
+    };
+    let elapsed = instant.elapsed();
+    println!("Elapsed: {:.7?}", elapsed);
+    let game = match serde_json::to_string(&calc) {
+        Ok(json) => json,
         Err(_) => {
-            return HttpResponse::InternalServerError().json(HTTPErrorResponse {
-                success: false,
-                message: "Failed to retrieve game from the database",
-            });
+            return HttpResponse::InternalServerError().json("Failed to serialize game data");
         }
+    };
+    HttpResponse::Ok().json(LastByCodeResponse {
+        success: true,
+        data: LastByCodeResponseData {
+            game_id: query_1.game_id,
+            summoner_name: query_1.summoner_name,
+            created_at: query_1.created_at,
+            game_code: query_1.game_code,
+            champion_name: query_1.champion_name,
+            game,
+        },
+    })
+}
+
+pub async fn matrix_by_code(
+    db: web::Data<DatabaseConnection>,
+    body: Result<web::Json<MatrixByCodeRequest>, actix_web::Error>,
+) -> impl Responder {
+    let data = match body {
+        Ok(data) => data.into_inner(),
+        Err(_) => return bad_request(),
+    };
+
+    let (query_1, game_props) = match last_game(db.get_ref(), data.code).await {
+        Ok(result) => result,
+        Err(response) => return response,
+    };
+
+    HttpResponse::Ok().json(MatrixByCodeResponse {
+        success: true,
+        data: MatrixByCodeResponseData {
+            game_id: query_1.game_id,
+            created_at: query_1.created_at,
+            game_code: query_1.game_code,
+            matrix: damage_matrix(game_props).await,
+        },
+    })
+}
+
+pub async fn sandbox_build(
+    body: Result<web::Json<GameSandboxRequest>, actix_web::Error>,
+) -> impl Responder {
+    let data = match body {
+        Ok(data) => data.into_inner(),
+        Err(_) => return bad_request(),
+    };
+
+    if !data.is_valid() {
+        return HttpResponse::BadRequest().json(HTTPErrorResponse {
+            success: false,
+            message: "Invalid sandbox: levels must be between 1 and 18 with valid ability ranks",
+        });
+    }
+
+    match sandbox(&data).await {
+        Ok(game_props) => HttpResponse::Ok().json(SandboxResponse {
+            success: true,
+            data: game_props,
+        }),
+        Err(_) => HttpResponse::BadRequest().json(HTTPErrorResponse {
+            success: false,
+            message: "Invalid sandbox: unknown champion or item",
+        }),
+    }
+}
+
+pub async fn champion_data_report(path: web::Path<String>) -> impl Responder {
+    let champion = path.into_inner();
+    match champion_report(&champion).await {
+        Ok(issues) => HttpResponse::Ok().json(ReportResponse {
+            success: true,
+            complete: issues.is_empty(),
+            champion,
+            issues,
+        }),
+        Err(_) => HttpResponse::NotFound().json(HTTPErrorResponse {
+            success: false,
+            message: "No local ability data found for the provided champion",
+        }),
     }
 }
@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+
+use crate::structs::{
+    game_struct::{
+        GameAbilities, GameChampionStats, GameCombo, GameKillReport, GamePlayer, GamePlayerDamage,
+        GamePlayerDamages, GameRelevant, GameResource, GameTeamfight, GameTeamfightRequest,
+    },
+    local_champion_struct::LocalChampion,
+    target_struct::RiotChampionTarget,
+};
+
+static COST_RESOURCES: [&str; 2] = ["MANA", "ENERGY"];
+
+pub fn resource_info(
+    stats: &GameChampionStats,
+    abilities: &GameAbilities,
+    local_champ: &LocalChampion,
+) -> GameResource {
+    let resource_type = match &stats.resource_type {
+        Some(t) => t.to_uppercase(),
+        None if stats.resource_max > 0.0 => String::from("MANA"),
+        None => String::from("NONE"),
+    };
+
+    let mut costs = HashMap::with_capacity(8);
+    if COST_RESOURCES.contains(&resource_type.as_str()) {
+        for (key, val) in local_champ {
+            let rank = match abilities.rank(key) {
+                Some(rank) if rank > 0 => rank,
+                _ => continue,
+            };
+            if let Some(cost) = val.cost.as_ref().and_then(|c| c.get((rank - 1) as usize)) {
+                costs.insert(key.clone(), *cost);
+            }
+        }
+    }
+
+    GameResource {
+        current: stats.resource_value.unwrap_or(stats.resource_max),
+        max: stats.resource_max,
+        resource_type,
+        costs,
+    }
+}
+
+pub fn default_combos(
+    relevant: &GameRelevant,
+    abilities: &GameAbilities,
+) -> Vec<(String, Vec<String>)> {
+    let mut learned: Vec<String> = relevant
+        .abilities
+        .min
+        .iter()
+        .filter(|key| key.as_str() != "A" && key.as_str() != "C")
+        .filter(|key| abilities.rank(key).is_none_or(|rank| rank > 0))
+        .cloned()
+        .collect();
+    learned.sort();
+
+    let mut extra = relevant.items.min.clone();
+    extra.extend(relevant.runes.min.iter().cloned());
+    extra.sort();
+
+    let mut combo = learned.clone();
+    combo.push(String::from("A"));
+    combo.extend(extra.iter().cloned());
+    combo.extend(relevant.spell.min.iter().cloned());
+
+    let mut rotation: Vec<String> = learned
+        .into_iter()
+        .filter(|key| !key.starts_with('R'))
+        .collect();
+    rotation.push(String::from("A"));
+    rotation.extend(extra);
+
+    vec![
+        (String::from("combo"), combo),
+        (String::from("rotation"), rotation),
+    ]
+}
+
+pub fn percent_ratio(value: f64) -> f64 {
+    if value > 1.0 {
+        value / 100.0
+    } else {
+        value
+    }
+}
+
+pub fn is_sustain(damage: &GamePlayerDamage) -> bool {
+    damage.damage_type == "heal" || damage.damage_type == "shield"
+}
+
+pub fn damage_entry<'a>(damages: &'a GamePlayerDamages, key: &str) -> Option<&'a GamePlayerDamage> {
+    damages
+        .abilities
+        .get(key)
+        .or_else(|| damages.items.get(key))
+        .or_else(|| damages.runes.get(key))
+        .or_else(|| damages.spell.get(key))
+}
+
+pub fn combo_damage(damages: &GamePlayerDamages, keys: &[String]) -> f64 {
+    keys.iter()
+        .filter_map(|key| damage_entry(damages, key))
+        .filter(|damage| !is_sustain(damage))
+        .fold(0.0, |total, damage| total + damage.min)
+}
+
+pub fn normalize_damages(
+    damages: &mut GamePlayerDamages,
+    cooldowns: &HashMap<String, f64>,
+    resource: &GameResource,
+) {
+    for (key, damage) in damages.abilities.iter_mut().chain(damages.items.iter_mut()) {
+        if is_sustain(damage) {
+            continue;
+        }
+        damage.per_second = cooldowns
+            .get(key)
+            .filter(|cd| **cd > 0.0)
+            .map(|cd| damage.min / cd);
+        damage.per_mana = resource
+            .costs
+            .get(key)
+            .filter(|cost| **cost > 0.0)
+            .map(|cost| damage.min / cost);
+    }
+}
+
+pub fn total_per_second(damages: &GamePlayerDamages) -> f64 {
+    damages
+        .abilities
+        .values()
+        .chain(damages.items.values())
+        .filter_map(|damage| damage.per_second)
+        .sum()
+}
+
+pub fn combo_cost(resource: &GameResource, keys: &[String]) -> f64 {
+    keys.iter().filter_map(|key| resource.costs.get(key)).sum()
+}
+
+pub fn evaluate_combos(
+    damages: &GamePlayerDamages,
+    resource: &GameResource,
+    combos: &[(String, Vec<String>)],
+) -> Vec<GameCombo> {
+    combos
+        .iter()
+        .map(|(name, keys)| {
+            let cost = combo_cost(resource, keys);
+            GameCombo {
+                name: name.clone(),
+                keys: keys.clone(),
+                damage: combo_damage(damages, keys),
+                cost,
+                affordable: cost <= resource.current,
+            }
+        })
+        .collect()
+}
+
+pub fn ability_cooldown(
+    champion: &RiotChampionTarget,
+    abilities: &GameAbilities,
+    key: &str,
+) -> Option<f64> {
+    let index = match key.chars().next()? {
+        'Q' => 0,
+        'W' => 1,
+        'E' => 2,
+        'R' => 3,
+        _ => return None,
+    };
+    let rank = abilities.rank(key).filter(|rank| *rank > 0)?;
+    champion
+        .spells
+        .get(index)?
+        .cooldown
+        .get((rank - 1) as usize)
+        .copied()
+}
+
+pub fn auto_attack_damage(damages: &GamePlayerDamages, stats: &GameChampionStats) -> f64 {
+    let attack = damages.abilities.get("A").map_or(0.0, |a| a.min);
+    let crit = damages.abilities.get("C").map_or(attack, |c| c.min);
+    let chance = percent_ratio(stats.crit_chance);
+    let onhit: f64 = damages
+        .items
+        .values()
+        .chain(damages.runes.values())
+        .filter(|damage| damage.onhit == Some(true))
+        .map(|damage| damage.min)
+        .sum();
+    attack + (crit - attack) * chance.clamp(0.0, 1.0) + onhit
+}
+
+pub fn sustained_dps(
+    damages: &GamePlayerDamages,
+    stats: &GameChampionStats,
+    cooldowns: &HashMap<String, f64>,
+    keys: &[String],
+) -> f64 {
+    let ability_dps: f64 = keys
+        .iter()
+        .filter_map(|key| {
+            let cooldown = cooldowns.get(key).filter(|cd| **cd > 0.0)?;
+            damage_entry(damages, key)
+                .filter(|damage| !is_sustain(damage))
+                .map(|damage| damage.min / cooldown)
+        })
+        .sum();
+    ability_dps + auto_attack_damage(damages, stats) * stats.attack_speed
+}
+
+pub fn kill_report(
+    damages: &GamePlayerDamages,
+    combos: &[GameCombo],
+    stats: &GameChampionStats,
+    cooldowns: &HashMap<String, f64>,
+    health: f64,
+) -> GameKillReport {
+    let best = combos
+        .iter()
+        .filter(|combo| combo.affordable)
+        .max_by(|a, b| a.damage.total_cmp(&b.damage));
+    let burst = best.map_or(0.0, |combo| combo.damage);
+
+    let dps = combos
+        .iter()
+        .find(|combo| combo.name == "rotation")
+        .map_or(0.0, |combo| {
+            sustained_dps(damages, stats, cooldowns, &combo.keys)
+        });
+
+    let remaining = (health - burst).max(0.0);
+    let time_to_kill = if remaining == 0.0 {
+        Some(0.0)
+    } else if dps > 0.0 {
+        Some(remaining / dps)
+    } else {
+        None
+    };
+
+    let lethal_health = combos
+        .iter()
+        .find(|combo| combo.name == "combo")
+        .map_or(0.0, |combo| combo.damage);
+    let auto = auto_attack_damage(damages, stats);
+    let leftover = (health - lethal_health).max(0.0);
+
+    GameKillReport {
+        combo: best.map(|combo| combo.name.clone()),
+        burst,
+        dps,
+        time_to_kill,
+        lethal_health,
+        lethal_percent: if health > 0.0 {
+            (lethal_health / health).min(1.0)
+        } else {
+            0.0
+        },
+        autos_after_combo: if leftover == 0.0 {
+            Some(0)
+        } else if auto > 0.0 {
+            Some((leftover / auto).ceil() as u32)
+        } else {
+            None
+        },
+    }
+}
+
+pub fn teamfight_damage(
+    request: &GameTeamfightRequest,
+    combos: &[(String, Vec<String>)],
+    enemies: &[GamePlayer],
+) -> Option<GameTeamfight> {
+    let targets: Vec<&GamePlayer> = enemies
+        .iter()
+        .filter(|p| p.damage.is_some() && p.champion_stats.is_some())
+        .collect();
+
+    let target = match &request.target {
+        Some(target) => target.clone(),
+        None => targets
+            .iter()
+            .min_by(|a, b| {
+                let a = a.champion_stats.unwrap().max_health;
+                let b = b.champion_stats.unwrap().max_health;
+                a.total_cmp(&b)
+            })?
+            .summoner_name
+            .clone(),
+    };
+
+    let keys = match &request.combo {
+        Some(keys) => keys.clone(),
+        None => combos
+            .iter()
+            .find(|(name, _)| name == "combo")
+            .map(|(_, keys)| keys.clone())
+            .unwrap_or_default(),
+    };
+
+    let mut damage: HashMap<String, f64> = targets
+        .iter()
+        .map(|p| (p.summoner_name.clone(), 0.0))
+        .collect();
+
+    for key in keys.iter() {
+        let area = targets.iter().any(|p| {
+            p.damage
+                .as_ref()
+                .and_then(|d| d.abilities.get(key))
+                .and_then(|d| d.area)
+                == Some(true)
+        });
+        for p in targets.iter() {
+            let hit = match request.hits.get(key) {
+                Some(names) => names.contains(&p.summoner_name),
+                None => area || p.summoner_name == target,
+            };
+            if hit {
+                *damage.get_mut(&p.summoner_name).unwrap() +=
+                    combo_damage(p.damage.as_ref().unwrap(), std::slice::from_ref(key));
+            }
+        }
+    }
+
+    let kills = targets
+        .iter()
+        .filter(|p| damage[&p.summoner_name] >= p.champion_stats.unwrap().max_health)
+        .map(|p| p.summoner_name.clone())
+        .collect();
+
+    Some(GameTeamfight {
+        total: damage.values().sum(),
+        target,
+        keys,
+        damage,
+        kills,
+    })
+}
@@ -1,5 +1,6 @@
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::error::Error;
 use std::sync::Arc;
 use std::sync::RwLock;
 use tokio::sync::RwLock as RwLockAsync;
@@ -7,16 +8,49 @@ use tokio::sync::RwLock as RwLockAsync;
 use futures::stream::FuturesUnordered;
 use futures::stream::StreamExt;
 
-use meval::eval_str;
-use regex::Regex;
+use meval::{eval_str_with_context, Context};
+use regex::{Captures, Regex};
+use serde_json::Value;
 
-use super::lol_service::{champion_api, item_api};
+use super::combo_service::{
+    ability_cooldown, auto_attack_damage, combo_damage, default_combos, evaluate_combos,
+    kill_report, normalize_damages, percent_ratio, resource_info, sustained_dps, teamfight_damage,
+    total_per_second,
+};
+use super::lol_service::{champion_api, champion_exists, champion_id, item_api};
+use super::mode_service::{apply_augments, damage_taken, game_mode};
+use super::rune_service::{
+    adaptive_physical, adaptive_split, adaptive_type, apply_adaptive, apply_keystones, apply_shard,
+    is_shard, keystone_modifier, rune_adaptive, rune_haste,
+};
+use super::stack_service::{apply_core, bought_stacks, stack_bonus, stack_count};
+use super::survival_service::{survivability, survivability_change, sustain};
+use super::target_service::{target_profile, SMITE_DAMAGE};
 use crate::structs::game_struct::GameAbilities;
+use crate::structs::game_struct::GameAllySupport;
+use crate::structs::game_struct::GameCalculateOptions;
+use crate::structs::game_struct::GameDamageMatrix;
 use crate::structs::game_struct::GameDamageReturn;
+use crate::structs::game_struct::GameDummyRequest;
+use crate::structs::game_struct::GameMatrixCell;
+use crate::structs::game_struct::GameMatrixRow;
+use crate::structs::game_struct::GameOverrides;
 use crate::structs::game_struct::GamePlayerDamage;
 use crate::structs::game_struct::GamePlayerDamages;
+use crate::structs::game_struct::GamePlayerItems;
+use crate::structs::game_struct::GamePlayerThreat;
 use crate::structs::game_struct::GamePlayerTool;
+use crate::structs::game_struct::GameSandboxRequest;
+use crate::structs::game_struct::GameScores;
+use crate::structs::game_struct::GameSurvivabilityTool;
+use crate::structs::game_struct::GameTargetDamage;
+use crate::structs::game_struct::GameTargetProfile;
+use crate::structs::game_struct::GameToolCompare;
 use crate::structs::game_struct::GameToolInfo;
+use crate::structs::game_struct::GameToolSet;
+use crate::structs::game_struct::GameWhatIf;
+use crate::structs::game_struct::{rank_caps, valid_ranks};
+use crate::structs::game_struct::{GameData, GameEvents, GameRuneProp, GameSummonerSpell};
 use crate::structs::local_champion_struct::LocalChampionAbility;
 use crate::structs::local_stats_struct::LocalStats;
 use crate::structs::target_struct::TargetReplacements;
@@ -51,10 +85,99 @@ static LOCAL_STATS: Lazy<Arc<LocalStats>> = Lazy::new(|| {
     Arc::new(fetch_json_sync::<LocalStats>("src/cache/stats").expect("Falha ao carregar stats"))
 });
 
-static LAST_CHAMP_ID: Lazy<RwLock<Option<String>>> = Lazy::new(|| RwLock::new(None));
+static LOCAL_CHAMP: Lazy<RwLock<HashMap<String, Arc<LocalChampion>>>> =
+    Lazy::new(|| RwLock::new(HashMap::with_capacity(10)));
+
+static LOCAL_CHAMP_REPORT: Lazy<RwLock<HashMap<String, Vec<String>>>> =
+    Lazy::new(|| RwLock::new(HashMap::with_capacity(10)));
+
+/** Unknown identifiers are left untouched so formula functions such as `clamp` still resolve */
+static FORMULA_IDENTIFIER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b[A-Za-z_]\w*\b").unwrap());
+
+thread_local! {
+    static FORMULA_CONTEXT: Context<'static> = formula_context();
+}
+
+/** Adds `if`, `clamp` and comparisons to the formula language, using 1.0 as true and 0.0 as false */
+fn formula_context() -> Context<'static> {
+    fn truth(value: bool) -> f64 {
+        if value {
+            1.0
+        } else {
+            0.0
+        }
+    }
+    let mut ctx = Context::new();
+    ctx.func3(
+        "if",
+        |cond, then, other| if cond != 0.0 { then } else { other },
+    );
+    ctx.func3("clamp", |value, low, high| value.max(low).min(high));
+    ctx.func2("gt", |a, b| truth(a > b));
+    ctx.func2("gte", |a, b| truth(a >= b));
+    ctx.func2("lt", |a, b| truth(a < b));
+    ctx.func2("lte", |a, b| truth(a <= b));
+    ctx.func2("eq", |a, b| truth(a == b));
+    ctx.func2("and", |a, b| truth(a != 0.0 && b != 0.0));
+    ctx.func2("or", |a, b| truth(a != 0.0 || b != 0.0));
+    ctx.func("not", |a| truth(a == 0.0));
+    ctx
+}
+
+async fn local_champion(id: &str) -> Result<Arc<LocalChampion>, Box<dyn Error>> {
+    if let Some(champ) = LOCAL_CHAMP.read().unwrap().get(id) {
+        return Ok(Arc::clone(champ));
+    }
 
-static LOCAL_CHAMP: Lazy<RwLock<LocalChampion>> =
-    Lazy::new(|| RwLock::new(HashMap::<String, LocalChampionAbility>::new()));
+    let mut champ = fetch_json::<LocalChampion>(&format!("src/champions/{}", id)).await?;
+    let report = validate_champion(&mut champ);
+    LOCAL_CHAMP_REPORT
+        .write()
+        .unwrap()
+        .insert(id.to_owned(), report);
+
+    let champ = Arc::new(champ);
+    LOCAL_CHAMP
+        .write()
+        .unwrap()
+        .insert(id.to_owned(), Arc::clone(&champ));
+
+    Ok(champ)
+}
+
+/** Reports unknown keys and short level or rank arrays, then drops and fills them so lookups stay in range */
+fn validate_champion(champ: &mut LocalChampion) -> Vec<String> {
+    let mut report = Vec::new();
+    champ.retain(|key, _| {
+        let known = matches!(key.chars().next(), Some('P' | 'Q' | 'W' | 'E' | 'R'));
+        if !known {
+            report.push(format!("{}: unknown ability key, skipped", key));
+        }
+        known
+    });
+    report.extend(champ.iter().flat_map(|(key, val)| val.validate(key)));
+    if !champ.keys().any(|key| key.starts_with('P')) {
+        report.push(String::from("P: no passive entries"));
+    }
+    report.sort();
+    for (key, val) in champ.iter_mut() {
+        val.fill_missing(key);
+    }
+    report
+}
+
+pub async fn champion_report(id: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    if !champion_exists(id) {
+        return Err("Unknown champion".into());
+    }
+    local_champion(id).await?;
+    Ok(LOCAL_CHAMP_REPORT
+        .read()
+        .unwrap()
+        .get(id)
+        .cloned()
+        .unwrap_or_default())
+}
 
 async fn assign_champion(data: GameProps) -> GameProps {
     let data_arc = Arc::new(RwLockAsync::new(data));
@@ -83,66 +206,88 @@ async fn assign_champion(data: GameProps) -> GameProps {
         }
     }
 
-    while let Some(_) = futures.next().await {}
+    while futures.next().await.is_some() {}
     Arc::try_unwrap(data_arc).unwrap().into_inner()
 }
 
-pub async fn calculate(mut data: GameProps, tool_item: &str) -> GameProps {
+pub async fn calculate(
+    mut data: GameProps,
+    tool_item: &str,
+    options: &GameCalculateOptions,
+) -> Result<GameProps, Box<dyn Error>> {
     data = assign_champion(data).await;
 
     let mut active_player = Arc::new(data.active_player);
+    let mut active_target: Option<(GamePlayer, GamePlayer)> = None;
+    let mut tool_active = Arc::new(GameActivePlayer::default());
+    let mut active_scores = GameScores::default();
+    let mut active_items = Vec::<String>::new();
+    let mut local_champ = Arc::new(LocalChampion::new());
     let all_players = data.all_players;
+    let mut allies = Vec::<GamePlayer>::with_capacity(4);
 
     for player in all_players.iter() {
         if player.summoner_name == active_player.summoner_name {
             if let Some(champion) = &player.champion {
-                {
-                    let last_champ_id = LAST_CHAMP_ID.read().unwrap();
-                    if last_champ_id.as_ref() != Some(&champion.id) {
-                        drop(last_champ_id);
-
-                        let champ =
-                            fetch_json::<LocalChampion>(&format!("src/champions/{}", &champion.id))
-                                .await
-                                .unwrap();
-
-                        let mut local_champ = LOCAL_CHAMP.write().unwrap();
-                        let mut last_champ_id = LAST_CHAMP_ID.write().unwrap();
+                local_champ = local_champion(&champion.id).await?;
+                allies = all_players
+                    .iter()
+                    .filter(|p| p.team == player.team && p.summoner_name != player.summoner_name)
+                    .cloned()
+                    .collect();
 
-                        *local_champ = champ;
-                        *last_champ_id = Some(champion.id.clone());
+                let acp = Arc::make_mut(&mut active_player);
+                acp.params = options.params.clone();
+                acp.form = options.form.clone();
+                acp.stacks = options.stacks.clone();
+                let mode = game_mode(
+                    data.game_data.map_number,
+                    options.mode.as_ref(),
+                    &champion.id,
+                    options.augments.as_ref(),
+                );
+                if mode.name == "ARENA" {
+                    if let Some(augments) = options.augments.as_ref() {
+                        apply_augments(&mut acp.champion_stats, augments);
                     }
                 }
-
-                let acp = Arc::make_mut(&mut active_player);
-
-                acp.team = Some(player.team.clone());
-                acp.champion = Some(champion.clone());
-                acp.champion_name = Some(champion.name.clone());
-                acp.skin = Some(player.skin_id);
-
-                acp.base_stats = Some(GameCoreStats::base_stats(&champion.stats, player.level));
-                acp.bonus_stats = Some(GameChampionStats::bonus_stats(
-                    &acp.champion_stats,
-                    acp.base_stats.unwrap(),
-                ));
-
-                acp.relevant = Some(GameRelevant {
-                    abilities: filter_abilities(&LOCAL_CHAMP.read().unwrap()),
-                    items: filter_items(
-                        &LOCAL_ITEMS,
-                        &player
-                            .items
+                acp.mode = Some(mode);
+                prepare_active(acp, player, &local_champ);
+                active_scores = player.scores.clone();
+                active_items = item_ids(player);
+
+                if let Some(set) = options.set.as_ref() {
+                    let set = &GameToolSet {
+                        add: set
+                            .add
                             .iter()
-                            .map(|item| item.item_id.to_string())
-                            .collect::<Vec<String>>(),
-                    ),
-                    runes: filter_runes(&LOCAL_RUNES, &acp.full_runes),
-                    spell: filter_spell(&player.summoner_spells),
-                });
-
-                {
-                    let path = &LOCAL_STATS.get(tool_item).unwrap();
+                            .filter(|id| item_on_map(id, data.game_data.map_number))
+                            .cloned()
+                            .collect(),
+                        sell: set
+                            .sell
+                            .iter()
+                            .filter(|id| active_items.contains(id))
+                            .cloned()
+                            .collect(),
+                    };
+                    acp.tool = Some(tool_set_info(set));
+                    let stacks = set
+                        .add
+                        .iter()
+                        .chain(set.sell.iter())
+                        .map(|id| {
+                            let stacks =
+                                item_stacks(id, &active_items, &active_scores, &options.stacks);
+                            (id.clone(), stacks)
+                        })
+                        .collect();
+                    tool_active = Arc::new(tool_set_player(set, acp.clone(), &stacks));
+                } else {
+                    let path = LOCAL_STATS
+                        .get(tool_item)
+                        .filter(|_| item_on_map(tool_item, data.game_data.map_number))
+                        .ok_or("Item não disponível no mapa")?;
                     let raw = &path.stats.raw;
                     let name = &path.name;
                     let gold = &path.gold.total;
@@ -150,69 +295,162 @@ pub async fn calculate(mut data: GameProps, tool_item: &str) -> GameProps {
                     acp.tool = Some(GameToolInfo {
                         id: tool_item.to_string(),
                         name: name.clone(),
-                        active: LOCAL_ITEMS.data.keys().find(|t| t == &tool_item).is_some(),
-                        gold: Some(gold.clone()),
+                        active: item_active(tool_item),
+                        gold: Some(*gold),
                         raw: raw.clone(),
+                        survivability: None,
                     });
+                    tool_active = Arc::new(tool_player(
+                        tool_item,
+                        acp.clone(),
+                        item_stacks(tool_item, &active_items, &active_scores, &options.stacks),
+                    ));
                 }
+
+                active_target = Some((
+                    active_perspective(acp, player),
+                    active_perspective(&tool_active, player),
+                ));
             }
         }
     }
 
     let mut futures = FuturesUnordered::new();
 
+    let map_number = data.game_data.map_number;
     let active_player_clone = Arc::clone(&active_player);
+    let active_target = Arc::new(active_target);
+    let compare_players: Arc<Vec<(String, GameActivePlayer)>> = Arc::new(
+        options
+            .compare
+            .iter()
+            .filter(|id| LOCAL_STATS.contains_key(id.as_str()))
+            .filter(|id| item_on_map(id, data.game_data.map_number))
+            .map(|id| {
+                (
+                    id.clone(),
+                    tool_player(
+                        id,
+                        (*active_player_clone).clone(),
+                        item_stacks(id, &active_items, &active_scores, &options.stacks),
+                    ),
+                )
+            })
+            .collect(),
+    );
 
     for mut player in all_players
         .into_iter()
         .filter(|p| &p.team != active_player_clone.team.as_ref().unwrap())
     {
         let active_player_clone = Arc::clone(&active_player);
+        let active_target = Arc::clone(&active_target);
+        let local_champ = Arc::clone(&local_champ);
+        let compare_players = Arc::clone(&compare_players);
+        let tool_active = Arc::clone(&tool_active);
         futures.push(async move {
+            prepare_player(&mut player).await;
             if let Some(champion) = &player.champion {
-                player.base_stats = Some(GameCoreStats::base_stats(&champion.stats, player.level));
-
-                let items: Vec<String> = player
-                    .items
-                    .iter()
-                    .map(|item| item.item_id.to_string())
-                    .collect();
-
-                player.champion_stats = Some(player_stats(player.base_stats.unwrap(), items).await);
-
-                player.bonus_stats = Some(GameCoreStats::bonus_stats(
-                    &player.champion_stats.unwrap(),
-                    &player.base_stats.unwrap(),
-                ));
-
                 let stats = all_stats(&player, &active_player_clone);
 
-                player.damage = Some(GamePlayerDamages {
-                    abilities: ability_damage(
-                        &stats,
+                player.damage = Some(player_damages(&stats, &active_player_clone, &local_champ));
+                let forms = champion_forms(&local_champ);
+                if !forms.is_empty() {
+                    player.forms = Some(
+                        forms
+                            .into_iter()
+                            .map(|form| {
+                                let damage = ability_damage(
+                                    &stats,
+                                    &active_player_clone.abilities,
+                                    &local_champ,
+                                    Some(&form),
+                                );
+                                (form, damage)
+                            })
+                            .collect(),
+                    );
+                }
+                player.combos = Some(evaluate_combos(
+                    player.damage.as_ref().unwrap(),
+                    active_player_clone.resource.as_ref().unwrap(),
+                    &default_combos(
+                        active_player_clone.relevant.as_ref().unwrap(),
                         &active_player_clone.abilities,
-                        &LOCAL_CHAMP.read().unwrap(),
                     ),
-                    items: item_damage(
-                        &stats,
-                        &active_player_clone.relevant.as_ref().unwrap().items.min,
-                        &LOCAL_ITEMS,
-                    ),
-                    runes: rune_damage(
-                        &stats,
-                        &active_player_clone.relevant.as_ref().unwrap().runes.min,
-                        &LOCAL_RUNES,
-                    ),
-                    spell: spell_damage(
-                        &active_player_clone.relevant.as_ref().unwrap().spell.min,
-                        active_player_clone.level,
-                    ),
-                });
-                player.tool = Some(tool_damage(
-                    structured_clone(&active_player_clone),
+                ));
+                player.kill = Some(kill_report(
+                    player.damage.as_ref().unwrap(),
+                    player.combos.as_ref().unwrap(),
+                    &active_player_clone.champion_stats,
+                    active_player_clone.cooldowns.as_ref().unwrap(),
+                    player.champion_stats.unwrap().max_health,
+                ));
+                player.sustain = player
+                    .combos
+                    .as_ref()
+                    .unwrap()
+                    .iter()
+                    .find(|combo| combo.name == "combo")
+                    .map(|combo| {
+                        sustain(
+                            player.damage.as_ref().unwrap(),
+                            &combo.keys,
+                            &active_player_clone.champion_stats,
+                            &rune_ids(&active_player_clone.full_runes),
+                            &item_ids(&player),
+                            &active_player_clone.stacks,
+                        )
+                    });
+                player.tool = Some(tool_compare(
+                    &tool_active,
+                    &active_player_clone,
                     &player,
-                    tool_item,
+                    &local_champ,
                 ));
+                if !compare_players.is_empty() {
+                    player.compare = Some(
+                        compare_players
+                            .iter()
+                            .map(|(id, tool_active)| {
+                                (
+                                    id.clone(),
+                                    tool_compare(
+                                        tool_active,
+                                        &active_player_clone,
+                                        &player,
+                                        &local_champ,
+                                    ),
+                                )
+                            })
+                            .collect(),
+                    );
+                }
+
+                if let Some((target, tool_target)) = active_target.as_ref() {
+                    if let Ok(enemy_champ) = local_champion(&champion.id).await {
+                        let mut threat = threat_damage(
+                            &player,
+                            target,
+                            &enemy_champ,
+                            active_player_clone.champion_stats.current_health,
+                            map_number,
+                            options.mode.as_ref(),
+                        );
+                        threat.tool_combo = Some(
+                            threat_damage(
+                                &player,
+                                tool_target,
+                                &enemy_champ,
+                                0.0,
+                                map_number,
+                                options.mode.as_ref(),
+                            )
+                            .combo,
+                        );
+                        player.threat = Some(threat);
+                    }
+                }
             }
             player
         });
@@ -225,73 +463,934 @@ pub async fn calculate(mut data: GameProps, tool_item: &str) -> GameProps {
 
     drop(active_player_clone);
 
-    GameProps {
-        active_player: Arc::try_unwrap(active_player).unwrap(),
+    let mut active_player = Arc::try_unwrap(active_player).unwrap();
+    if active_player.team.is_some() {
+        let threats: Vec<(String, f64)> = all_players_collected
+            .iter()
+            .filter_map(|p| {
+                p.threat
+                    .as_ref()
+                    .map(|t| (p.summoner_name.clone(), t.combo))
+            })
+            .collect();
+        let tool_threats: Vec<(String, f64)> = all_players_collected
+            .iter()
+            .filter_map(|p| {
+                p.threat
+                    .as_ref()
+                    .and_then(|t| t.tool_combo)
+                    .map(|c| (p.summoner_name.clone(), c))
+            })
+            .collect();
+
+        let current = survivability(&active_player.champion_stats, &threats);
+        let max = survivability(&tool_active.champion_stats, &tool_threats);
+
+        if let Some(tool) = active_player.tool.as_mut() {
+            tool.survivability = Some(GameSurvivabilityTool {
+                dif: survivability_change(&max, &current),
+                max,
+            });
+        }
+        active_player.survivability = Some(current);
+
+        if let Some(request) = options.teamfight.as_ref() {
+            active_player.teamfight = teamfight_damage(
+                request,
+                &default_combos(
+                    active_player.relevant.as_ref().unwrap(),
+                    &active_player.abilities,
+                ),
+                &all_players_collected,
+            );
+        }
+
+        if local_champ.values().any(|val| val.targets_ally()) {
+            let mut support = Vec::with_capacity(allies.len());
+            for mut ally in allies {
+                prepare_player(&mut ally).await;
+                if ally.champion_stats.is_none() {
+                    continue;
+                }
+                let abilities = ally_support(
+                    &all_stats(&ally, &active_player),
+                    &active_player,
+                    &local_champ,
+                );
+                let max = ally_support(&all_stats(&ally, &tool_active), &tool_active, &local_champ);
+                let tool = max
+                    .iter()
+                    .filter_map(|(key, next)| {
+                        abilities
+                            .get(key)
+                            .map(|curr| (key.clone(), evaluate_change(next, curr)))
+                    })
+                    .collect();
+                support.push(GameAllySupport {
+                    summoner_name: ally.summoner_name,
+                    champion_name: ally.champion_name,
+                    abilities,
+                    tool: Some(tool),
+                });
+            }
+            active_player.allies = Some(support);
+        }
+
+        let mut profiles: Vec<GameTargetProfile> = options
+            .targets
+            .iter()
+            .filter_map(|id| target_profile(id, data.game_data.game_time))
+            .collect();
+        if let Some(dummy) = options.dummy.as_ref() {
+            profiles.push(dummy_profile(dummy).await);
+        }
+        if !compare_players.is_empty() {
+            active_player.compare = Some(
+                compare_players
+                    .iter()
+                    .map(|(id, _)| {
+                        let path = LOCAL_STATS.get(id).unwrap();
+                        GameToolCompare {
+                            id: id.clone(),
+                            name: path.name.clone(),
+                            gold: path.gold.total,
+                            sum: all_players_collected
+                                .iter()
+                                .filter_map(|p| p.compare.as_ref()?.get(id))
+                                .map(|tool| tool.sum)
+                                .sum(),
+                            dps: all_players_collected
+                                .iter()
+                                .filter_map(|p| p.compare.as_ref()?.get(id)?.dps)
+                                .sum(),
+                        }
+                    })
+                    .collect(),
+            );
+        }
+
+        if !profiles.is_empty() {
+            active_player.targets = Some(
+                profiles
+                    .iter()
+                    .map(|profile| target_damage(profile, &active_player, &local_champ))
+                    .collect(),
+            );
+        }
+    }
+
+    Ok(GameProps {
+        active_player,
         all_players: all_players_collected,
         events: data.events,
         game_data: data.game_data,
+    })
+}
+
+pub async fn damage_matrix(mut data: GameProps) -> GameDamageMatrix {
+    data = assign_champion(data).await;
+
+    let mut active_player = data.active_player;
+    let mut all_players = data.all_players;
+
+    for player in all_players.iter_mut() {
+        if player.summoner_name != active_player.summoner_name {
+            prepare_player(player).await;
+            continue;
+        }
+        if let Some(champion) = &player.champion {
+            if let Ok(local_champ) = local_champion(&champion.id).await {
+                active_player.mode = Some(game_mode(
+                    data.game_data.map_number,
+                    None,
+                    &champion.id,
+                    None,
+                ));
+                prepare_active(&mut active_player, player, &local_champ);
+                *player = active_perspective(&active_player, player);
+            }
+        }
+    }
+
+    let mut matrix = GameDamageMatrix::with_capacity(all_players.len());
+
+    for entry in all_players.iter() {
+        let champion = match &entry.champion {
+            Some(champion) if entry.champion_stats.is_some() => champion,
+            _ => continue,
+        };
+        let local_champ = match local_champion(&champion.id).await {
+            Ok(local_champ) => local_champ,
+            Err(_) => continue,
+        };
+        let attacker = if entry.summoner_name == active_player.summoner_name {
+            structured_clone(&active_player)
+        } else {
+            enemy_perspective(entry, &local_champ, data.game_data.map_number, None)
+        };
+        let combos = default_combos(attacker.relevant.as_ref().unwrap(), &attacker.abilities);
+
+        let targets = all_players
+            .iter()
+            .filter(|p| p.team != entry.team && p.champion_stats.is_some())
+            .map(|target| {
+                let stats = all_stats(target, &attacker);
+                let damage = player_damages(&stats, &attacker, &local_champ);
+                let mut cell = GameMatrixCell {
+                    summoner_name: target.summoner_name.clone(),
+                    champion_name: target.champion_name.clone(),
+                    combo: 0.0,
+                    dps: 0.0,
+                };
+                for (name, keys) in combos.iter() {
+                    match name.as_str() {
+                        "combo" => cell.combo = combo_damage(&damage, keys),
+                        "rotation" => {
+                            cell.dps = sustained_dps(
+                                &damage,
+                                &attacker.champion_stats,
+                                attacker.cooldowns.as_ref().unwrap(),
+                                keys,
+                            )
+                        }
+                        _ => continue,
+                    }
+                }
+                cell
+            })
+            .collect();
+
+        matrix.push(GameMatrixRow {
+            summoner_name: entry.summoner_name.clone(),
+            champion_name: entry.champion_name.clone(),
+            team: entry.team.clone(),
+            targets,
+        });
+    }
+
+    matrix
+}
+
+async fn apply_overrides(
+    data: GameProps,
+    overrides: &GameOverrides,
+    stacks: &HashMap<String, u32>,
+) -> Result<GameProps, Box<dyn Error>> {
+    let mut data = assign_champion(data).await;
+    let active_player = &mut data.active_player;
+
+    if let Some(player) = data
+        .all_players
+        .iter_mut()
+        .find(|p| p.summoner_name == active_player.summoner_name)
+    {
+        if let Some(champion) = &player.champion {
+            let level = overrides.level.unwrap_or(player.level);
+            let items = overrides.items.as_ref().map_or_else(
+                || player.items.clone(),
+                |ids| {
+                    ids.iter()
+                        .map(|id| GamePlayerItems { item_id: *id })
+                        .collect()
+                },
+            );
+            let ids: Vec<String> = items.iter().map(|i| i.item_id.to_string()).collect();
+
+            let curr_ids = item_ids(player);
+            let curr = player_stats(
+                GameCoreStats::base_stats(&champion.stats, player.level),
+                curr_ids.clone(),
+            )
+            .await;
+            let curr = stacked_stats(curr, &curr_ids, &player.scores, stacks);
+            let next = player_stats(
+                GameCoreStats::base_stats(&champion.stats, level),
+                ids.clone(),
+            )
+            .await;
+            let next = stacked_stats(next, &ids, &player.scores, stacks);
+            active_player.champion_stats.apply_delta(&next, &curr);
+
+            player.level = level;
+            player.items = items;
+            active_player.level = level;
+        }
+    }
+
+    if let Some(ranks) = overrides.abilities {
+        if !valid_ranks(ranks, active_player.level) {
+            return Err("Níveis de habilidade inválidos para o nível".into());
+        }
+        active_player.abilities =
+            GameAbilities::from_ranks(&active_player.abilities.passive.display_name, ranks);
+    }
+    if let Some(runes) = overrides.runes.as_ref() {
+        active_player.full_runes.general_runes = runes
+            .iter()
+            .map(|id| GameRuneProp {
+                display_name: String::new(),
+                id: *id,
+            })
+            .collect();
+    }
+    for (summoner_name, items) in overrides.enemies.iter() {
+        if let Some(player) = data
+            .all_players
+            .iter_mut()
+            .find(|p| &p.summoner_name == summoner_name)
+        {
+            player.items = items
+                .iter()
+                .map(|id| GamePlayerItems { item_id: *id })
+                .collect();
+        }
+    }
+
+    Ok(data)
+}
+
+pub async fn what_if(
+    data: GameProps,
+    tool_item: &str,
+    options: &GameCalculateOptions,
+    overrides: &GameOverrides,
+) -> Result<GameProps, Box<dyn Error>> {
+    let overridden = apply_overrides(data.clone(), overrides, &options.stacks).await?;
+    let original = calculate(data, tool_item, options).await?;
+    let mut result = calculate(overridden, tool_item, options).await?;
+
+    for player in result.all_players.iter_mut() {
+        let min = original
+            .all_players
+            .iter()
+            .find(|p| p.summoner_name == player.summoner_name)
+            .and_then(|p| p.damage.as_ref());
+        if let (Some(max), Some(min)) = (player.damage.as_ref(), min) {
+            let change = tool_change(max, min);
+            player.what_if = Some(GameWhatIf {
+                original: min.clone(),
+                dif: change.dif,
+                sum: change.sum,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn champion_forms(local_champ: &LocalChampion) -> Vec<String> {
+    let mut forms: Vec<String> = local_champ
+        .values()
+        .filter_map(|val| val.form.clone())
+        .collect();
+    forms.sort();
+    forms.dedup();
+    forms
+}
+
+fn champion_form(
+    local_champ: &LocalChampion,
+    requested: Option<&String>,
+    attack_range: f64,
+) -> Option<String> {
+    let forms = champion_forms(local_champ);
+    let range = if attack_range > 350.0 {
+        "ranged"
+    } else {
+        "melee"
+    };
+    requested
+        .filter(|form| forms.contains(form))
+        .or_else(|| forms.iter().find(|form| form.as_str() == range))
+        .or_else(|| forms.first())
+        .cloned()
+}
+
+fn item_on_map(item: &str, map_number: u8) -> bool {
+    LOCAL_STATS
+        .get(item)
+        .and_then(|path| path.maps.get(&map_number.to_string()))
+        .copied()
+        .unwrap_or(true)
+}
+
+/** Item actives ignore ability haste */
+fn active_cooldowns(active_player: &GameActivePlayer) -> HashMap<String, f64> {
+    let relevant = active_player.relevant.as_ref().unwrap();
+    let champion = active_player.champion.as_ref().unwrap();
+    let haste = active_player.champion_stats.ability_haste
+        + active_player
+            .mode
+            .as_ref()
+            .map_or(0.0, |mode| mode.ability_haste);
+    let abilities = relevant.abilities.min.iter().filter_map(|key| {
+        ability_cooldown(champion, &active_player.abilities, key)
+            .map(|cd| (key.clone(), cd * 100.0 / (100.0 + haste)))
+    });
+    let items = relevant.items.min.iter().filter_map(|key| {
+        LOCAL_ITEMS
+            .data
+            .get(key)
+            .filter(|val| val.active)
+            .and_then(|val| val.cooldown)
+            .map(|cd| (key.clone(), cd))
+    });
+    abilities.chain(items).collect()
+}
+
+fn prepare_active(acp: &mut GameActivePlayer, player: &GamePlayer, local_champ: &LocalChampion) {
+    let champion = player.champion.as_ref().unwrap();
+    acp.form = champion_form(
+        local_champ,
+        acp.form.as_ref(),
+        acp.champion_stats.attack_range,
+    );
+
+    acp.team = Some(player.team.clone());
+    acp.champion = Some(champion.clone());
+    acp.champion_name = Some(champion.name.clone());
+    acp.skin = Some(player.skin_id);
+
+    acp.base_stats = Some(GameCoreStats::base_stats(&champion.stats, player.level));
+    let bonus_attack_damage =
+        acp.champion_stats.attack_damage - acp.base_stats.unwrap().attack_damage;
+    apply_keystones(
+        &mut acp.champion_stats,
+        bonus_attack_damage,
+        &rune_ids(&acp.full_runes),
+        &acp.stacks,
+        acp.level,
+    );
+    acp.bonus_stats = Some(GameChampionStats::bonus_stats(
+        &acp.champion_stats,
+        acp.base_stats.unwrap(),
+    ));
+    refresh_adaptive(acp);
+
+    acp.relevant = Some(GameRelevant {
+        abilities: filter_abilities(local_champ, acp.form.as_deref()),
+        items: filter_items(&LOCAL_ITEMS, &item_ids(player)),
+        runes: filter_runes(&LOCAL_RUNES, &acp.full_runes),
+        spell: filter_spell(&player.summoner_spells),
+    });
+
+    acp.cooldowns = Some(active_cooldowns(acp));
+    acp.resource = Some(resource_info(
+        &acp.champion_stats,
+        &acp.abilities,
+        local_champ,
+    ));
+}
+
+async fn prepare_player(player: &mut GamePlayer) {
+    if let Some(champion) = &player.champion {
+        player.base_stats = Some(GameCoreStats::base_stats(&champion.stats, player.level));
+        let items = item_ids(player);
+        let core = player_stats(player.base_stats.unwrap(), items.clone()).await;
+        player.champion_stats = Some(stacked_stats(core, &items, &player.scores, &HashMap::new()));
+        player.bonus_stats = Some(GameCoreStats::bonus_stats(
+            &player.champion_stats.unwrap(),
+            &player.base_stats.unwrap(),
+        ));
+    }
+}
+
+async fn dummy_profile(request: &GameDummyRequest) -> GameTargetProfile {
+    let mut profile = target_profile("TargetDummy", 0.0).unwrap();
+    let base = GameCoreStats {
+        max_health: request.max_health,
+        armor: request.armor,
+        magic_resist: request.magic_resist,
+        ..Default::default()
+    };
+    let items: Vec<String> = request.items.iter().map(|id| id.to_string()).collect();
+
+    profile.stats = player_stats(base, items).await;
+    profile.current_health = request
+        .current_health
+        .map(|health| health.min(profile.stats.max_health));
+    profile.items = request
+        .items
+        .iter()
+        .map(|id| GamePlayerItems { item_id: *id })
+        .collect();
+    profile
+}
+
+fn target_damage(
+    profile: &GameTargetProfile,
+    active_player: &GameActivePlayer,
+    local_champ: &LocalChampion,
+) -> GameTargetDamage {
+    let target = GamePlayer {
+        champion_name: profile.name.clone(),
+        summoner_name: profile.id.clone(),
+        level: active_player.level,
+        items: profile.items.clone(),
+        base_stats: Some(profile.stats),
+        champion_stats: Some(profile.stats),
+        bonus_stats: Some(GameCoreStats::default()),
+        current_health: profile.current_health,
+        ..Default::default()
+    };
+    let relevant = active_player.relevant.as_ref().unwrap();
+    let stats = all_stats(&target, active_player);
+    let mut damage = player_damages(&stats, active_player, local_champ);
+
+    damage.spell.clear();
+    if !profile.abilities {
+        damage.abilities.retain(|key, _| key == "A" || key == "C");
+        damage.items.retain(|_, val| val.onhit == Some(true));
+        damage.runes.clear();
+    }
+    for section in [&mut damage.abilities, &mut damage.items, &mut damage.runes] {
+        for val in section.values_mut() {
+            val.min *= profile.damage_mod;
+            val.max = val.max.map(|max| max * profile.damage_mod);
+        }
+    }
+
+    let combo = default_combos(relevant, &active_player.abilities)
+        .into_iter()
+        .find(|(name, _)| name == "combo")
+        .map_or(0.0, |(_, keys)| combo_damage(&damage, &keys));
+    let smite = if profile.smite && relevant.spell.min.contains(&"SummonerSmite".to_owned()) {
+        Some(SMITE_DAMAGE)
+    } else {
+        None
+    };
+    let auto = auto_attack_damage(&damage, &active_player.champion_stats);
+    let health = profile.current_health.unwrap_or(profile.stats.max_health);
+
+    GameTargetDamage {
+        id: profile.id.clone(),
+        name: profile.name.clone(),
+        health,
+        combo,
+        smite,
+        secure_health: combo + smite.unwrap_or(0.0),
+        autos_to_kill: if auto > 0.0 {
+            Some((health / auto).ceil() as u32)
+        } else {
+            None
+        },
+        damage,
+    }
+}
+
+fn rune_ids(runes: &GameFullRunes) -> Vec<String> {
+    runes
+        .general_runes
+        .iter()
+        .map(|rune| rune.id.to_string())
+        .collect()
+}
+
+fn item_ids(player: &GamePlayer) -> Vec<String> {
+    player
+        .items
+        .iter()
+        .map(|item| item.item_id.to_string())
+        .collect()
+}
+
+fn player_damages(
+    stats: &TargetAllStats,
+    active_player: &GameActivePlayer,
+    local_champ: &LocalChampion,
+) -> GamePlayerDamages {
+    let relevant = active_player.relevant.as_ref().unwrap();
+    let mut damages = GamePlayerDamages {
+        abilities: ability_damage(
+            stats,
+            &active_player.abilities,
+            local_champ,
+            active_player.form.as_deref(),
+        ),
+        items: item_damage(stats, &relevant.items.min, &LOCAL_ITEMS),
+        runes: rune_damage(stats, &relevant.runes.min, &LOCAL_RUNES),
+        spell: spell_damage(&relevant.spell.min, active_player.level),
+    };
+    if let (Some(cooldowns), Some(resource)) = (&active_player.cooldowns, &active_player.resource) {
+        normalize_damages(&mut damages, cooldowns, resource);
+    }
+    damages
+}
+
+fn estimated_ranks(level: u8) -> [u8; 4] {
+    let [cap, _, _, ultimate] = rank_caps(level);
+    let mut points = level.saturating_sub(ultimate);
+    let mut ranks = [0u8; 3];
+    for rank in ranks.iter_mut() {
+        if points > 0 {
+            *rank += 1;
+            points -= 1;
+        }
+    }
+    for rank in ranks.iter_mut() {
+        let add = points.min(cap - *rank);
+        *rank += add;
+        points -= add;
+    }
+    [ranks[0], ranks[1], ranks[2], ultimate]
+}
+
+fn enemy_perspective(
+    player: &GamePlayer,
+    local_champ: &LocalChampion,
+    map_number: u8,
+    requested: Option<&String>,
+) -> GameActivePlayer {
+    let champion = player.champion.as_ref().unwrap();
+    let full_runes = GameFullRunes::default();
+    let form = champion_form(local_champ, None, champion.stats.attackrange);
+
+    let mut attacker = GameActivePlayer {
+        summoner_name: player.summoner_name.clone(),
+        level: player.level,
+        abilities: GameAbilities::from_ranks(&champion.passive.name, estimated_ranks(player.level)),
+        champion_stats: GameChampionStats::from_core(
+            &player.champion_stats.unwrap(),
+            &champion.stats,
+            player.level,
+        ),
+        relevant: Some(GameRelevant {
+            abilities: filter_abilities(local_champ, form.as_deref()),
+            items: filter_items(&LOCAL_ITEMS, &item_ids(player)),
+            runes: filter_runes(&LOCAL_RUNES, &full_runes),
+            spell: filter_spell(&player.summoner_spells),
+        }),
+        form,
+        full_runes,
+        champion_name: Some(champion.name.clone()),
+        champion: Some(champion.clone()),
+        dragon: player.dragon.clone(),
+        base_stats: player.base_stats,
+        bonus_stats: player.bonus_stats,
+        team: Some(player.team.clone()),
+        skin: Some(player.skin_id),
+        mode: Some(game_mode(map_number, requested, &champion.id, None)),
+        ..Default::default()
+    };
+    attacker.cooldowns = Some(active_cooldowns(&attacker));
+    attacker
+}
+
+fn sandbox_player(
+    summoner_name: String,
+    champion_name: &str,
+    level: u8,
+    items: &[u32],
+    spells: &[String],
+    team: &str,
+) -> GamePlayer {
+    let spell = |index: usize| GameSummonerSpell {
+        display_name: spells.get(index).cloned().unwrap_or_default(),
+        raw_description: spells.get(index).cloned().unwrap_or_default(),
+    };
+    GamePlayer {
+        champion_name: champion_name.to_owned(),
+        level,
+        summoner_name,
+        items: items
+            .iter()
+            .map(|id| GamePlayerItems { item_id: *id })
+            .collect(),
+        summoner_spells: GameSummonerSpells {
+            summoner_spell_one: spell(0),
+            summoner_spell_two: spell(1),
+        },
+        team: team.to_owned(),
+        ..Default::default()
+    }
+}
+
+pub async fn sandbox(request: &GameSandboxRequest) -> Result<GameProps, Box<dyn Error>> {
+    if let Some(enemy) = request
+        .enemies
+        .iter()
+        .find(|enemy| champion_id(&enemy.champion).is_none())
+    {
+        return Err(format!("Campeão não encontrado: {}", enemy.champion).into());
+    }
+    let champion = champion_api(&request.champion).await?;
+    local_champion(&champion.id).await?;
+    if !LOCAL_STATS.contains_key(&request.item) {
+        return Err("Item não encontrado".into());
+    }
+
+    let items: Vec<String> = request.items.iter().map(|id| id.to_string()).collect();
+    let base = GameCoreStats::base_stats(&champion.stats, request.level);
+    let mut stats =
+        GameChampionStats::from_core(&base, &champion.stats, request.level).to_hashmap_camel();
+    for item in items.iter() {
+        let stacks = item_stacks(
+            item,
+            &items,
+            &GameScores::default(),
+            &request.options.stacks,
+        );
+        apply_modifiers(item, &mut stats, 1.0, stacks, base.attack_damage);
+    }
+    let mut champion_stats = GameChampionStats::from_hashmap_camel(stats);
+    champion_stats.current_health = champion_stats.max_health;
+    for id in request.runes.iter() {
+        let bonus_attack_damage = champion_stats.attack_damage - base.attack_damage;
+        if is_shard(*id) {
+            apply_shard(&mut champion_stats, bonus_attack_damage, *id, request.level);
+            continue;
+        }
+        let rune = id.to_string();
+        let force = rune_adaptive(
+            &rune,
+            request.level,
+            request.options.stacks.get(&rune).copied(),
+            request.game_time.unwrap_or(0.0) / 60.0,
+        );
+        apply_adaptive(&mut champion_stats, bonus_attack_damage, force);
+        champion_stats.ability_haste += rune_haste(&rune, request.level);
     }
+
+    let summoner_name = String::from("Sandbox");
+    let mut all_players = Vec::with_capacity(request.enemies.len() + 1);
+    all_players.push(sandbox_player(
+        summoner_name.clone(),
+        &request.champion,
+        request.level,
+        &request.items,
+        &request.spells,
+        "ORDER",
+    ));
+    for (index, enemy) in request.enemies.iter().enumerate() {
+        all_players.push(sandbox_player(
+            format!("Enemy {}", index + 1),
+            &enemy.champion,
+            enemy.level,
+            &enemy.items,
+            &[],
+            "CHAOS",
+        ));
+    }
+
+    let active_player = GameActivePlayer {
+        summoner_name,
+        level: request.level,
+        abilities: GameAbilities::from_ranks(
+            &champion.passive.name,
+            request
+                .abilities
+                .unwrap_or_else(|| estimated_ranks(request.level)),
+        ),
+        champion_stats,
+        full_runes: GameFullRunes {
+            general_runes: request
+                .runes
+                .iter()
+                .map(|id| GameRuneProp {
+                    display_name: String::new(),
+                    id: *id,
+                })
+                .collect(),
+        },
+        ..Default::default()
+    };
+
+    let props = GameProps {
+        active_player,
+        all_players,
+        events: GameEvents { events: Vec::new() },
+        game_data: GameData {
+            game_time: request.game_time.unwrap_or(0.0),
+            map_number: 11,
+        },
+    };
+
+    calculate(props, &request.item, &request.options).await
+}
+
+fn active_perspective(active_player: &GameActivePlayer, player: &GamePlayer) -> GamePlayer {
+    let cs = &active_player.champion_stats;
+    let mut target = player.clone();
+    target.base_stats = active_player.base_stats;
+    target.champion_stats = Some(GameCoreStats {
+        max_health: cs.max_health,
+        armor: cs.armor,
+        magic_resist: cs.magic_resist,
+        attack_damage: cs.attack_damage,
+        resource_max: cs.resource_max,
+        ability_power: cs.ability_power,
+    });
+    target.bonus_stats = Some(GameCoreStats::bonus_stats(
+        &target.champion_stats.unwrap(),
+        &target.base_stats.unwrap(),
+    ));
+    target
 }
 
-fn assing_stats(item: &str, active_player: &mut GameActivePlayer) -> HashMap<String, f64> {
-    let mut stats = active_player.champion_stats.into_hashmap_camel();
+fn threat_damage(
+    player: &GamePlayer,
+    target: &GamePlayer,
+    local_champ: &LocalChampion,
+    current_health: f64,
+    map_number: u8,
+    requested: Option<&String>,
+) -> GamePlayerThreat {
+    let attacker = enemy_perspective(player, local_champ, map_number, requested);
+    let stats = all_stats(target, &attacker);
+    let damage = player_damages(&stats, &attacker, local_champ);
+    let combo = default_combos(attacker.relevant.as_ref().unwrap(), &attacker.abilities)
+        .into_iter()
+        .find(|(name, _)| name == "combo")
+        .map_or(0.0, |(_, keys)| combo_damage(&damage, &keys));
+
+    GamePlayerThreat {
+        damage,
+        combo,
+        percent: if current_health > 0.0 {
+            combo / current_health
+        } else {
+            0.0
+        },
+        tool_combo: None,
+    }
+}
+
+fn assing_stats(
+    item: &str,
+    active_player: &mut GameActivePlayer,
+    stacks: u32,
+) -> HashMap<String, f64> {
+    let mut stats = active_player.champion_stats.to_hashmap_camel();
+    let base_attack_damage = active_player.base_stats.unwrap().attack_damage;
+    apply_modifiers(item, &mut stats, 1.0, stacks, base_attack_damage);
+    stats
+}
+
+/** Owned items infer their stacks from scores while items about to be bought do not */
+fn item_stacks(
+    item: &str,
+    owned: &[String],
+    scores: &GameScores,
+    requested: &HashMap<String, u32>,
+) -> u32 {
+    match LOCAL_STATS.get(item) {
+        Some(path) if path.stack && owned.iter().any(|id| id == item) => {
+            stack_count(item, scores, requested)
+        }
+        Some(path) if path.stack => bought_stacks(item, owned, scores, requested),
+        _ => 0,
+    }
+}
+
+fn stacked_stats(
+    mut core: GameCoreStats,
+    items: &[String],
+    scores: &GameScores,
+    requested: &HashMap<String, u32>,
+) -> GameCoreStats {
+    for item in items {
+        if let Some((stat, value)) = stack_bonus(item, item_stacks(item, items, scores, requested))
+        {
+            apply_core(&mut core, stat, value);
+        }
+    }
+    core
+}
+
+/** `sign` is 1.0 when buying the item and -1.0 when selling it */
+fn apply_modifiers(
+    item: &str,
+    stats: &mut HashMap<String, f64>,
+    sign: f64,
+    stacks: u32,
+    base_attack_damage: f64,
+) {
+    if let Some((stat, value)) = stack_bonus(item, stacks) {
+        if let Some(k) = stats.get_mut(stat) {
+            *k += sign * value;
+        }
+    }
     if let Some(item) = &LOCAL_STATS.get(item) {
         let modifiers = &item.stats.modifiers;
-        for (key, val) in modifiers.into_iter() {
+        if let Some(force) = modifiers.get("adaptiveForce").and_then(|v| v.as_f64()) {
+            let (attack_damage, ability_power) = adaptive_split(
+                stats["attackDamage"] - base_attack_damage,
+                stats["abilityPower"],
+                force,
+            );
+            *stats.get_mut("attackDamage").unwrap() += sign * attack_damage;
+            *stats.get_mut("abilityPower").unwrap() += sign * ability_power;
+        }
+        for (key, val) in modifiers.iter() {
             if let Some(k) = stats.get_mut(key) {
                 match val.to_string().parse::<f64>() {
-                    Ok(v) => *k += v,
+                    Ok(v) => *k += sign * v,
                     Err(_) => {
                         let v = val.as_str().map(|s| s.replace("%", ""));
-                        *k -= v.unwrap().parse::<f64>().unwrap_or(0.0);
+                        *k -= sign * v.unwrap().parse::<f64>().unwrap_or(0.0);
                     }
                 }
             }
         }
     }
-    stats
 }
 
 fn evaluate_change(next: &GamePlayerDamage, curr: &GamePlayerDamage) -> GamePlayerDamage {
     GamePlayerDamage {
         min: next.min - curr.min,
-        max: if next.max.is_some() && curr.max.is_some() {
-            Some(next.max.unwrap() - curr.max.unwrap())
-        } else {
-            None
+        max: match (next.max, curr.max) {
+            (Some(next_max), Some(curr_max)) => Some(next_max - curr_max),
+            _ => None,
         },
         damage_type: next.damage_type.clone(),
         name: next.name.clone(),
         area: next.area,
         onhit: next.onhit,
+        active: next.active,
+        per_second: match (next.per_second, curr.per_second) {
+            (Some(next_dps), Some(curr_dps)) => Some(next_dps - curr_dps),
+            _ => None,
+        },
+        per_mana: match (next.per_mana, curr.per_mana) {
+            (Some(next_mana), Some(curr_mana)) => Some(next_mana - curr_mana),
+            _ => None,
+        },
+    }
+}
+
+fn zeroed_damage(damage: &GamePlayerDamage) -> GamePlayerDamage {
+    GamePlayerDamage {
+        min: 0.0,
+        max: damage.max.map(|_| 0.0),
+        per_second: damage.per_second.map(|_| 0.0),
+        per_mana: damage.per_mana.map(|_| 0.0),
+        ..damage.clone()
     }
 }
 
 fn process_change(
-    at: &str,
     val: &HashMap<String, GamePlayerDamage>,
     min_at: &HashMap<String, GamePlayerDamage>,
     change_at: &mut HashMap<String, GamePlayerDamage>,
     sum: &mut f64,
 ) {
-    for (k, v) in val.iter() {
-        match min_at.get(k.as_str()) {
-            Some(curr) => {
-                let result = evaluate_change(v, curr);
-                *sum += result.min;
-                if let Some(max) = result.max {
-                    *sum += max;
-                }
-                change_at.insert(k.to_owned(), result);
-            }
-            None => {
-                println!(
-                    "Key is {}, and was not found in both directions on {}",
-                    k, at
-                );
-                continue;
-            }
+    /* Keys present on only one side come from bought or sold items and count against zero */
+    let added = val
+        .iter()
+        .map(|(k, next)| (k, next.clone(), min_at.get(k).cloned()));
+    let removed = min_at
+        .iter()
+        .filter(|(k, _)| !val.contains_key(*k))
+        .map(|(k, curr)| (k, zeroed_damage(curr), Some(curr.clone())));
+
+    for (k, next, curr) in added.chain(removed) {
+        let curr = curr.unwrap_or_else(|| zeroed_damage(&next));
+        let result = evaluate_change(&next, &curr);
+        *sum += result.min;
+        if let Some(max) = result.max {
+            *sum += max;
         }
+        change_at.insert(k.to_owned(), result);
     }
 }
 
@@ -306,38 +1405,19 @@ fn find_change(
         runes: GameDamageReturn::new(),
         spell: GameDamageReturn::new(),
     };
-    let max_hashmap = max.into_hashmap();
-    let min_hashmap = min.into_hashmap();
+    let max_hashmap = max.to_hashmap();
+    let min_hashmap = min.to_hashmap();
     for (key, val) in max_hashmap.into_iter() {
         match key {
             "abilities" => process_change(
-                key,
-                &val,
+                val,
                 min_hashmap.get(key).unwrap(),
                 &mut change.abilities,
                 sum,
             ),
-            "items" => process_change(
-                key,
-                &val,
-                min_hashmap.get(key).unwrap(),
-                &mut change.items,
-                sum,
-            ),
-            "runes" => process_change(
-                key,
-                &val,
-                min_hashmap.get(key).unwrap(),
-                &mut change.runes,
-                sum,
-            ),
-            "spell" => process_change(
-                key,
-                &val,
-                min_hashmap.get(key).unwrap(),
-                &mut change.spell,
-                sum,
-            ),
+            "items" => process_change(val, min_hashmap.get(key).unwrap(), &mut change.items, sum),
+            "runes" => process_change(val, min_hashmap.get(key).unwrap(), &mut change.runes, sum),
+            "spell" => process_change(val, min_hashmap.get(key).unwrap(), &mut change.spell, sum),
             _ => continue,
         }
     }
@@ -350,49 +1430,151 @@ fn tool_change(max: &GamePlayerDamages, min: &GamePlayerDamages) -> TargetToolCh
     TargetToolChange { dif, sum }
 }
 
-fn tool_damage(
-    mut active_player: GameActivePlayer,
-    player: &GamePlayer,
-    item: &str,
-) -> GamePlayerTool {
-    let assigned_stats = assing_stats(item, &mut active_player);
+fn tool_player(item: &str, mut active_player: GameActivePlayer, stacks: u32) -> GameActivePlayer {
+    let current = active_player.champion_stats.clone();
+    let assigned_stats = assing_stats(item, &mut active_player, stacks);
     active_player.champion_stats = GameChampionStats::from_hashmap_camel(assigned_stats);
+    active_player.champion_stats.resource_type = current.resource_type;
+    active_player.champion_stats.current_health +=
+        active_player.champion_stats.max_health - current.max_health;
     active_player.bonus_stats = Some(GameChampionStats::bonus_stats(
         &active_player.champion_stats,
         active_player.base_stats.unwrap(),
     ));
+    refresh_adaptive(&mut active_player);
+    active_player.cooldowns = Some(active_cooldowns(&active_player));
+    active_player
+}
 
-    let stats = all_stats(&player, &active_player);
+fn refresh_adaptive(active_player: &mut GameActivePlayer) {
+    let bonus = active_player.bonus_stats.unwrap();
+    active_player.adaptive = Some(adaptive_type(
+        bonus.attack_damage,
+        active_player.champion_stats.ability_power,
+    ));
+}
 
-    let damage_max = GamePlayerDamages {
-        abilities: ability_damage(
-            &stats,
-            &active_player.abilities,
-            &LOCAL_CHAMP.read().unwrap(),
-        ),
-        items: item_damage(
-            &stats,
-            &active_player.relevant.as_ref().unwrap().items.min,
-            &LOCAL_ITEMS,
-        ),
-        runes: rune_damage(
-            &stats,
-            &active_player.relevant.as_ref().unwrap().runes.min,
-            &LOCAL_RUNES,
-        ),
-        spell: spell_damage(
-            &active_player.relevant.as_ref().unwrap().spell.min,
-            active_player.level,
-        ),
-    };
+fn item_active(item: &str) -> bool {
+    LOCAL_ITEMS.data.get(item).is_some_and(|val| val.active)
+}
+
+fn tool_set_info(set: &GameToolSet) -> GameToolInfo {
+    let added: Vec<_> = set
+        .add
+        .iter()
+        .filter_map(|id| LOCAL_STATS.get(id).map(|path| (id, path)))
+        .collect();
+    let cost: u32 = added.iter().map(|(_, path)| path.gold.total).sum();
+    let refund: u32 = set
+        .sell
+        .iter()
+        .filter_map(|id| LOCAL_STATS.get(id))
+        .map(|path| path.gold.sell)
+        .sum();
+
+    let mut raw: HashMap<String, Value> = HashMap::new();
+    for (_, path) in added.iter() {
+        for (key, val) in path.stats.raw.iter() {
+            let total = match (raw.get(key).and_then(Value::as_f64), val.as_f64()) {
+                (Some(prev), Some(next)) => Value::from(prev + next),
+                _ => val.clone(),
+            };
+            raw.insert(key.clone(), total);
+        }
+    }
+
+    GameToolInfo {
+        id: set.add.join(","),
+        name: added
+            .iter()
+            .map(|(_, path)| path.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" + "),
+        active: added.iter().any(|(id, _)| item_active(id)),
+        gold: Some(cost.saturating_sub(refund)),
+        raw,
+        survivability: None,
+    }
+}
+
+fn tool_set_player(
+    set: &GameToolSet,
+    mut active_player: GameActivePlayer,
+    stacks: &HashMap<String, u32>,
+) -> GameActivePlayer {
+    let current = active_player.champion_stats.clone();
+    let mut stats = current.to_hashmap_camel();
+    let base_attack_damage = active_player.base_stats.unwrap().attack_damage;
+    for item in set.add.iter() {
+        apply_modifiers(item, &mut stats, 1.0, stacks[item], base_attack_damage);
+    }
+    for item in set.sell.iter() {
+        apply_modifiers(item, &mut stats, -1.0, stacks[item], base_attack_damage);
+    }
+    active_player.champion_stats = GameChampionStats::from_hashmap_camel(stats);
+    active_player.champion_stats.resource_type = current.resource_type;
+    active_player.champion_stats.current_health =
+        (current.current_health + active_player.champion_stats.max_health - current.max_health)
+            .max(0.0);
+    active_player.bonus_stats = Some(GameChampionStats::bonus_stats(
+        &active_player.champion_stats,
+        active_player.base_stats.unwrap(),
+    ));
+
+    if let Some(relevant) = active_player.relevant.as_mut() {
+        let items = &mut relevant.items;
+        items.min.retain(|key| !set.sell.contains(key));
+        items.max.retain(|key| !set.sell.contains(key));
+        for item in set.add.iter() {
+            if let Some(val) = LOCAL_ITEMS.data.get(item) {
+                if !items.min.contains(item) {
+                    items.min.push(item.clone());
+                }
+                if val.max.is_some() && !items.max.contains(item) {
+                    items.max.push(item.clone());
+                }
+            }
+        }
+    }
+    active_player.cooldowns = Some(active_cooldowns(&active_player));
+    refresh_adaptive(&mut active_player);
+    active_player
+}
 
-    let change = tool_change(&damage_max, &player.damage.as_ref().unwrap());
+fn cooldown_change(
+    next: &GameActivePlayer,
+    curr: &GameActivePlayer,
+) -> Option<HashMap<String, f64>> {
+    let next = next.cooldowns.as_ref()?;
+    let curr = curr.cooldowns.as_ref()?;
+    Some(
+        next.iter()
+            .filter_map(|(key, cd)| curr.get(key).map(|prev| (key.clone(), cd - prev)))
+            .collect(),
+    )
+}
+
+fn tool_compare(
+    active_player: &GameActivePlayer,
+    current: &GameActivePlayer,
+    player: &GamePlayer,
+    local_champ: &LocalChampion,
+) -> GamePlayerTool {
+    let stats = all_stats(player, active_player);
+
+    let damage_max = player_damages(&stats, active_player, local_champ);
+
+    let current_damage = player.damage.as_ref().unwrap();
+    let change = tool_change(&damage_max, current_damage);
+    let dps = total_per_second(&damage_max) - total_per_second(current_damage);
 
     GamePlayerTool {
         sum: change.sum,
         dif: Some(change.dif),
         max: damage_max,
         rec: None,
+        cooldowns: cooldown_change(active_player, current),
+        dps: Some(dps),
     }
 }
 
@@ -441,6 +1623,15 @@ fn json_replacements(stats: &TargetAllStats) -> TargetReplacements {
         ("expectedMR", m.magic_resist),
         ("expectedAD", m.attack_damage),
         ("expectedBonusHealth", y.bonus_stats.max_health),
+        ("expectedCurrentHealth", y.current_health),
+        (
+            "expectedMissingHealth",
+            if m.max_health > 0.0 {
+                1.0 - y.current_health / m.max_health
+            } else {
+                0.0
+            },
+        ),
     ];
 
     entries
@@ -455,34 +1646,32 @@ fn evaluate(
     rep: &TargetAllStats,
     inc: Option<TargetReplacements>,
 ) -> (f64, Option<f64>) {
-    let mut replacements: TargetReplacements = json_replacements(&rep);
-    if let Some(custom_replacements) = inc {
-        replacements.extend(custom_replacements);
-    }
-    fn eval_expression(expr: &str) -> Option<f64> {
-        match eval_str(expr) {
-            Ok(result) => Some(result),
-            Err(_) => None,
-        }
-    }
-    fn result(t: Option<&String>, replacements: &TargetReplacements) -> Option<f64> {
-        t.map(|expr| {
-            let mut expr = expr.clone();
-            for (key, value) in replacements {
-                let re = Regex::new(&format!(r"\b{}\b", key)).unwrap();
-                expr = re.replace_all(&expr, &value.to_string()).to_string();
-            }
-            eval_expression(&expr).unwrap_or(0.0)
-        })
-    }
-    let res_min = result(Some(min), &replacements).unwrap();
-    let res_max = result(max, &replacements);
-    (res_min, res_max)
+    let result = |expr: &String| evaluate_formula(expr, &rep.replacements, inc.as_ref());
+    (result(min), max.map(result))
+}
+
+/** Params in `inc` take precedence over the built-in replacements */
+fn evaluate_formula(
+    expr: &str,
+    replacements: &TargetReplacements,
+    inc: Option<&TargetReplacements>,
+) -> f64 {
+    let value = |key: &str| {
+        inc.and_then(|custom| custom.get(key))
+            .or_else(|| replacements.get(key))
+            .copied()
+    };
+    let expr = FORMULA_IDENTIFIER.replace_all(expr, |caps: &Captures| {
+        value(&caps[0]).map_or_else(|| caps[0].to_owned(), |v| v.to_string())
+    });
+    FORMULA_CONTEXT
+        .with(|ctx| eval_str_with_context(&expr, ctx).ok())
+        .unwrap_or(0.0)
 }
 
 fn rune_damage(
     stats: &TargetAllStats,
-    runes: &Vec<String>,
+    runes: &[String],
     local_runes: &LocalRunes,
 ) -> GameDamageReturn {
     let mut result = GameDamageReturn::with_capacity(6);
@@ -492,17 +1681,18 @@ fn rune_damage(
         let element = local_runes.data.get(rune);
         match element {
             Some(val) => {
-                let min_str: &String;
-                match form.as_str() {
-                    "melee" => {
-                        min_str = &val.min.melee;
-                    }
-                    "ranged" => {
-                        min_str = &val.min.ranged;
-                    }
+                let min_str = match form.as_str() {
+                    "melee" => &val.min.melee,
+                    "ranged" => &val.min.ranged,
                     _ => break,
-                }
-                let (min, _) = evaluate(min_str, None, stats, None);
+                };
+                let stacks = stats.active_player.stacks.get(rune).copied().unwrap_or(0);
+                let (min, _) = evaluate(
+                    min_str,
+                    None,
+                    stats,
+                    Some(HashMap::from([("stacks".to_owned(), stacks as f64)])),
+                );
                 result.insert(
                     rune.clone(),
                     GamePlayerDamage {
@@ -511,6 +1701,9 @@ fn rune_damage(
                         damage_type: val.rune_type.clone(),
                         name: Some(val.name.clone()),
                         onhit: None,
+                        active: None,
+                        per_second: None,
+                        per_mana: None,
                         area: None,
                     },
                 );
@@ -523,7 +1716,7 @@ fn rune_damage(
 
 fn item_damage(
     stats: &TargetAllStats,
-    items: &Vec<String>,
+    items: &[String],
     local_items: &LocalItems,
 ) -> GameDamageReturn {
     let mut result = GameDamageReturn::with_capacity(6);
@@ -533,32 +1726,20 @@ fn item_damage(
         let element = local_items.data.get(item);
         match element {
             Some(val) => {
-                let min_str: &String;
-                let max_str: Option<&String>;
-                match form.as_str() {
-                    "melee" => {
-                        min_str = &val.min.melee;
-                        max_str = val.max.as_ref().and_then(|x| Some(&x.melee));
-                    }
-                    "ranged" => {
-                        min_str = &val.min.ranged;
-                        max_str = val.max.as_ref().and_then(|x| Some(&x.ranged));
-                    }
+                let (min_str, max_str) = match form.as_str() {
+                    "melee" => (&val.min.melee, val.max.as_ref().map(|x| &x.melee)),
+                    "ranged" => (&val.min.ranged, val.max.as_ref().map(|x| &x.ranged)),
                     _ => break,
-                }
-                let mut total: Option<f64> = None;
-                if let Some(t) = val.effect.as_ref() {
-                    total = Some(t[(stats.active_player.level - 1) as usize]);
-                }
+                };
+                let total = val
+                    .effect
+                    .as_ref()
+                    .map(|t| t[(stats.active_player.level - 1) as usize]);
                 let (min, max) = evaluate(
                     min_str,
                     max_str,
                     stats,
-                    if total.is_some() {
-                        Some(HashMap::from([("total".to_owned(), total.unwrap())]))
-                    } else {
-                        None
-                    },
+                    total.map(|t| HashMap::from([("total".to_owned(), t)])),
                 );
                 result.insert(
                     item.clone(),
@@ -568,6 +1749,9 @@ fn item_damage(
                         damage_type: val.item_type.clone(),
                         name: Some(val.name.clone()),
                         onhit: Some(val.onhit),
+                        active: Some(val.active),
+                        per_second: None,
+                        per_mana: None,
                         area: None,
                     },
                 );
@@ -578,7 +1762,7 @@ fn item_damage(
     result
 }
 
-fn spell_damage(spells: &Vec<String>, level: u8) -> GameDamageReturn {
+fn spell_damage(spells: &[String], level: u8) -> GameDamageReturn {
     let mut result = GameDamageReturn::with_capacity(1);
     for spell in spells {
         if spell == "SummonerDot" {
@@ -590,6 +1774,9 @@ fn spell_damage(spells: &Vec<String>, level: u8) -> GameDamageReturn {
                     damage_type: String::from("true"),
                     name: Some(String::from("Ignite")),
                     onhit: None,
+                    active: None,
+                    per_second: None,
+                    per_mana: None,
                     area: None,
                 },
             );
@@ -598,40 +1785,70 @@ fn spell_damage(spells: &Vec<String>, level: u8) -> GameDamageReturn {
     result
 }
 
+fn ability_entry(
+    stats: &TargetAllStats,
+    abilities: &GameAbilities,
+    key: &str,
+    val: &LocalChampionAbility,
+) -> GamePlayerDamage {
+    let index: usize = match abilities.rank(key) {
+        Some(0) => return GamePlayerDamage::void(),
+        Some(rank) => (rank - 1).into(),
+        None if key.starts_with('P') => val.level_index(stats.active_player.level),
+        None => return GamePlayerDamage::void(),
+    };
+    let min_str = match val.min.get(index) {
+        Some(min_str) => min_str,
+        None => return GamePlayerDamage::void(),
+    };
+    let max_str = val.max.as_ref().and_then(|t| t.get(index));
+
+    let params = val.resolve_params(&stats.active_player.params);
+    let (min, max) = evaluate(min_str, max_str, stats, Some(params));
+
+    GamePlayerDamage {
+        min,
+        max,
+        damage_type: val.ability_type.clone(),
+        name: None,
+        area: val.area,
+        onhit: None,
+        active: None,
+        per_second: None,
+        per_mana: None,
+    }
+}
+
+fn ally_support(
+    stats: &TargetAllStats,
+    active_player: &GameActivePlayer,
+    local_champ: &LocalChampion,
+) -> GameDamageReturn {
+    let power = 1.0 + percent_ratio(active_player.champion_stats.heal_shield_power);
+    local_champ
+        .iter()
+        .filter(|(_, val)| val.targets_ally() && val.in_form(active_player.form.as_deref()))
+        .map(|(key, val)| {
+            let mut entry = ability_entry(stats, &active_player.abilities, key, val);
+            entry.min *= power;
+            entry.max = entry.max.map(|max| max * power);
+            (key.clone(), entry)
+        })
+        .collect()
+}
+
 fn ability_damage(
     stats: &TargetAllStats,
     abilities: &GameAbilities,
     local_champ: &LocalChampion,
+    form: Option<&str>,
 ) -> GameDamageReturn {
     let mut result = GameDamageReturn::with_capacity(8);
     for (key, val) in local_champ {
-        let index: usize = match key.chars().next().unwrap() {
-            'Q' => (abilities.q.ability_level - 1).into(),
-            'W' => (abilities.w.ability_level - 1).into(),
-            'E' => (abilities.e.ability_level - 1).into(),
-            'R' => (abilities.r.ability_level - 1).into(),
-            'P' => (stats.active_player.level - 1).into(),
-            _ => panic!("Unknown key: {}", key),
-        };
-        if index == 0 {
-            result.insert(key.clone(), GamePlayerDamage::void());
+        if val.targets_ally() || !val.in_form(form) {
+            continue;
         }
-        let min_str = &val.min[index];
-        let max_str = val.max.as_ref().and_then(|t| t.get(index));
-
-        let (min, max) = evaluate(min_str, max_str, stats, None);
-
-        result.insert(
-            key.clone(),
-            GamePlayerDamage {
-                min,
-                max,
-                damage_type: val.ability_type.clone(),
-                name: None,
-                area: None,
-                onhit: None,
-            },
-        );
+        result.insert(key.clone(), ability_entry(stats, abilities, key, val));
     }
     let acst = &stats.active_player.champion_stats;
     let attack = acst.attack_damage * stats.active_player.multiplier.physical;
@@ -644,6 +1861,9 @@ fn ability_damage(
             name: None,
             area: None,
             onhit: None,
+            active: None,
+            per_second: None,
+            per_mana: None,
         },
     );
     result.insert(
@@ -655,6 +1875,9 @@ fn ability_damage(
             name: None,
             area: None,
             onhit: None,
+            active: None,
+            per_second: None,
+            per_mana: None,
         },
     );
     result
@@ -664,7 +1887,7 @@ fn all_stats(player: &GamePlayer, active_player: &GameActivePlayer) -> TargetAll
     let acs = &active_player.champion_stats;
     let abs = &active_player.bonus_stats.unwrap();
     let abt = &active_player.base_stats.unwrap();
-    let rel = &active_player.relevant.as_ref().unwrap();
+    let rel = active_player.relevant.as_ref().unwrap();
 
     let pcs = &player.champion_stats.unwrap();
     let pbs = &player.bonus_stats.unwrap();
@@ -678,12 +1901,6 @@ fn all_stats(player: &GamePlayer, active_player: &GameActivePlayer) -> TargetAll
     let rar = pcs.armor * acs.armor_penetration_percent - acs.armor_penetration_flat;
     let rmr = pcs.magic_resist * acs.magic_penetration_percent - acs.magic_penetration_flat;
 
-    let physical = 100.0 / (100.0 + rar);
-    let magic = 100.0 / (100.0 + rmr);
-
-    let adp = 0.35 * abs.attack_damage >= 0.2 * acs.ability_power;
-    let add = if adp { physical } else { magic };
-
     let ohp = pcs.max_health / acs.max_health;
     let ehp = pcs.max_health - acs.max_health;
     let mshp = 1.0 - acs.current_health / acs.max_health;
@@ -702,6 +1919,22 @@ fn all_stats(player: &GamePlayer, active_player: &GameActivePlayer) -> TargetAll
         acp_mod += exhp / (220000.0 / 15.0);
     }
 
+    /* General modifiers and map balance scale both damage types on top of resistances */
+    let mut balance = acp_mod * pgen_mod;
+    if let Some(mode) = active_player.mode.as_ref() {
+        balance *= mode.damage_dealt;
+        if let Some(champion) = player.champion.as_ref() {
+            balance *= damage_taken(mode, &champion.id);
+        }
+    }
+
+    let exposure = 1.0 + keystone_modifier(&rel.runes.min, &active_player.stacks);
+    let physical = 100.0 / (100.0 + rar) * exposure * balance;
+    let magic = 100.0 / (100.0 + rmr) * exposure * balance;
+
+    let adp = adaptive_physical(abs.attack_damage, acs.ability_power);
+    let add = if adp { physical } else { magic };
+
     let form = if acs.attack_range > 350.0 {
         "ranged".to_owned()
     } else {
@@ -714,7 +1947,7 @@ fn all_stats(player: &GamePlayer, active_player: &GameActivePlayer) -> TargetAll
         "magic".to_owned()
     };
 
-    TargetAllStats {
+    let mut stats = TargetAllStats {
         active_player: AllStatsActivePlayer {
             id: active_player.champion.as_ref().unwrap().id.clone(),
             level: active_player.level,
@@ -760,8 +1993,11 @@ fn all_stats(player: &GamePlayer, active_player: &GameActivePlayer) -> TargetAll
                 attack_damage: abs.attack_damage,
                 ability_power: abs.ability_power,
             },
+            params: active_player.params.clone(),
+            stacks: active_player.stacks.clone(),
         },
         player: AllStatsPlayer {
+            current_health: player.current_health.unwrap_or(pcs.max_health),
             multiplier: AllStatsMultiplier {
                 magic: pmag_mod,
                 physical: pphy_mod,
@@ -834,12 +2070,18 @@ fn all_stats(player: &GamePlayer, active_player: &GameActivePlayer) -> TargetAll
                 1.0
             },
         },
-    }
+        replacements: TargetReplacements::new(),
+    };
+    stats.replacements = json_replacements(&stats);
+    stats
 }
 
 async fn player_stats(mut base: GameCoreStats, items: Vec<String>) -> GameCoreStats {
     for item in items {
-        let res = item_api(&item).await.unwrap();
+        let res = match item_api(&item).await {
+            Ok(res) => res,
+            Err(_) => continue,
+        };
         let stats = res.stats;
         for (key, val) in stats.iter() {
             match key.as_str() {
@@ -848,7 +2090,7 @@ async fn player_stats(mut base: GameCoreStats, items: Vec<String>) -> GameCoreSt
                 "FlatMagicDamageMod" => base.ability_power += val,
                 "FlatArmorMod" => base.armor += val,
                 "FlatSpellBlockMod" => base.magic_resist += val,
-                "FlatPhysicalDamageMod" => base.ability_power += val,
+                "FlatPhysicalDamageMod" => base.attack_damage += val,
                 _ => continue,
             }
         }
@@ -856,10 +2098,13 @@ async fn player_stats(mut base: GameCoreStats, items: Vec<String>) -> GameCoreSt
     base
 }
 
-fn filter_abilities(_champion: &LocalChampion) -> GameRelevantProps {
+fn filter_abilities(_champion: &LocalChampion, form: Option<&str>) -> GameRelevantProps {
     let mut min = Vec::with_capacity(8);
     let mut max = Vec::with_capacity(8);
-    for (key, val) in _champion.iter() {
+    for (key, val) in _champion
+        .iter()
+        .filter(|(_, val)| !val.targets_ally() && val.in_form(form))
+    {
         if val.max.is_some() {
             max.push(key.clone());
         }
@@ -870,7 +2115,7 @@ fn filter_abilities(_champion: &LocalChampion) -> GameRelevantProps {
     GameRelevantProps { min, max }
 }
 
-fn filter_items(_items: &LocalItems, items: &Vec<String>) -> GameRelevantProps {
+fn filter_items(_items: &LocalItems, items: &[String]) -> GameRelevantProps {
     let mut min = Vec::with_capacity(6);
     let mut max = Vec::with_capacity(6);
     for (key, val) in _items.data.iter() {
@@ -887,11 +2132,7 @@ fn filter_items(_items: &LocalItems, items: &Vec<String>) -> GameRelevantProps {
 fn filter_runes(_runes: &LocalRunes, runes: &GameFullRunes) -> GameRelevantProps {
     let mut min = Vec::with_capacity(6);
     let mut max = Vec::with_capacity(6);
-    let rune_vec: Vec<String> = runes
-        .general_runes
-        .iter()
-        .map(|r| r.id.to_string())
-        .collect();
+    let rune_vec = rune_ids(runes);
     for (key, val) in _runes.data.iter() {
         if rune_vec.contains(key) {
             min.push(key.clone());
@@ -914,6 +2155,81 @@ fn filter_spell(spells: &GameSummonerSpells) -> GameRelevantProps {
         if spell.contains("SummonerDot") {
             min.push(String::from("SummonerDot"));
         }
+        if spell.contains("SummonerSmite") {
+            min.push(String::from("SummonerSmite"));
+        }
     }
     GameRelevantProps { min, max }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{estimated_ranks, evaluate_formula};
+    use crate::structs::game_struct::valid_ranks;
+
+    #[test]
+    fn estimated_ranks_start_with_one_basic_ability() {
+        assert_eq!(estimated_ranks(1), [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn estimated_ranks_take_the_ultimate_at_six() {
+        assert_eq!(estimated_ranks(6), [3, 1, 1, 1]);
+    }
+
+    #[test]
+    fn estimated_ranks_max_every_ability_at_eighteen() {
+        assert_eq!(estimated_ranks(18), [5, 5, 5, 3]);
+    }
+
+    #[test]
+    fn estimated_ranks_are_valid_at_every_level() {
+        assert!((1..=18).all(|level| valid_ranks(estimated_ranks(level), level)));
+    }
+
+    #[test]
+    fn valid_ranks_reject_an_ultimate_before_six() {
+        assert!(!valid_ranks([2, 1, 1, 1], 5));
+    }
+
+    #[test]
+    fn valid_ranks_cap_basic_abilities_by_level() {
+        assert!(!valid_ranks([4, 1, 0, 0], 5));
+        assert!(valid_ranks([3, 1, 1, 0], 5));
+    }
+
+    fn replacements() -> HashMap<String, f64> {
+        HashMap::from([("x".to_owned(), 10.0), ("y".to_owned(), -3.0)])
+    }
+
+    #[test]
+    fn evaluate_formula_supports_conditionals_and_comparisons() {
+        let rep = replacements();
+        assert_eq!(evaluate_formula("if(gt(x, 5), 2, 3)", &rep, None), 2.0);
+        assert_eq!(evaluate_formula("if(lt(x, 5), 2, 3)", &rep, None), 3.0);
+        assert_eq!(evaluate_formula("clamp(x * 20, 0, 100)", &rep, None), 100.0);
+    }
+
+    #[test]
+    fn evaluate_formula_prefers_params_over_replacements() {
+        let params = HashMap::from([("x".to_owned(), 4.0)]);
+        assert_eq!(
+            evaluate_formula("x * 2", &replacements(), Some(&params)),
+            8.0
+        );
+    }
+
+    #[test]
+    fn evaluate_formula_passes_unknown_identifiers_through() {
+        assert_eq!(evaluate_formula("max(x, 12)", &replacements(), None), 12.0);
+    }
+
+    #[test]
+    fn evaluate_formula_handles_negative_replacements() {
+        let rep = replacements();
+        assert_eq!(evaluate_formula("y*-15", &rep, None), 45.0);
+        assert_eq!(evaluate_formula("10-y", &rep, None), 13.0);
+    }
+}
@@ -24,15 +24,19 @@ static IDS_CACHE: Lazy<HashMap<String, HashMap<String, String>>> = Lazy::new(||
 static CHAMPION_CACHE: Lazy<RwLock<HashMap<String, RiotChampionTarget>>> =
     Lazy::new(|| RwLock::new(HashMap::with_capacity(10)));
 
+pub fn champion_exists(champion: &str) -> bool {
+    IDS_CACHE.contains_key(champion)
+}
+
+pub fn champion_id(champion: &str) -> Option<String> {
+    IDS_CACHE
+        .iter()
+        .find(|(_, val)| val.values().any(|v| v == champion))
+        .map(|(key, _)| key.clone())
+}
+
 async fn get_champion(champion: &str) -> String {
-    for (key, val) in IDS_CACHE.iter() {
-        for (_, v) in val.iter() {
-            if v == champion {
-                return key.clone();
-            }
-        }
-    }
-    String::from("TargetDummy")
+    champion_id(champion).unwrap_or_else(|| String::from("TargetDummy"))
 }
 
 pub async fn item_api(item: &str) -> Result<RiotItemTarget, Box<dyn Error>> {
@@ -1,2 +1,8 @@
+pub mod combo_service;
 pub mod game_service;
 pub mod lol_service;
+pub mod mode_service;
+pub mod rune_service;
+pub mod stack_service;
+pub mod survival_service;
+pub mod target_service;
@@ -0,0 +1,63 @@
+use once_cell::sync::Lazy;
+
+use crate::fetch_json_sync;
+use crate::structs::game_struct::{GameAugments, GameChampionStats, GameMode};
+use crate::structs::local_modes_struct::{LocalModeBalance, LocalModes};
+
+/** Balance data is optional, so a missing file leaves every champion unchanged */
+static LOCAL_MODES: Lazy<LocalModes> =
+    Lazy::new(|| fetch_json_sync::<LocalModes>("src/cache/modes").unwrap_or_default());
+
+static URF_HASTE: f64 = 300.0;
+
+pub fn mode_name(map_number: u8, requested: Option<&String>) -> String {
+    match requested {
+        Some(mode) => mode.to_uppercase(),
+        None => match map_number {
+            12 => String::from("ARAM"),
+            30 => String::from("ARENA"),
+            _ => String::from("CLASSIC"),
+        },
+    }
+}
+
+pub fn mode_balance(mode: &str, champion_id: &str) -> Option<LocalModeBalance> {
+    LOCAL_MODES.get(mode)?.get(champion_id).copied()
+}
+
+pub fn game_mode(
+    map_number: u8,
+    requested: Option<&String>,
+    champion_id: &str,
+    augments: Option<&GameAugments>,
+) -> GameMode {
+    let name = mode_name(map_number, requested);
+    let mut damage_dealt = mode_balance(&name, champion_id).map_or(1.0, |b| b.dealt);
+    if name == "ARENA" {
+        if let Some(augments) = augments {
+            damage_dealt *= 1.0 + augments.damage;
+        }
+    }
+    GameMode {
+        ability_haste: if name == "URF" { URF_HASTE } else { 0.0 },
+        damage_dealt,
+        name,
+        map_number,
+    }
+}
+
+pub fn apply_augments(stats: &mut GameChampionStats, augments: &GameAugments) {
+    let mut map = stats.to_hashmap_camel();
+    for (key, value) in augments.stats.iter() {
+        if let Some(stat) = map.get_mut(key) {
+            *stat += value;
+        }
+    }
+    let resource_type = stats.resource_type.take();
+    *stats = GameChampionStats::from_hashmap_camel(map);
+    stats.resource_type = resource_type;
+}
+
+pub fn damage_taken(mode: &GameMode, champion_id: &str) -> f64 {
+    mode_balance(&mode.name, champion_id).map_or(1.0, |b| b.taken)
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use crate::structs::game_struct::GameChampionStats;
+
+pub static CONQUEROR_MAX: u32 = 12;
+static LETHAL_TEMPO_MAX: u32 = 6;
+static PRESS_THE_ATTACK_HITS: u32 = 3;
+static PRESS_THE_ATTACK_EXPOSURE: f64 = 0.08;
+
+fn level_scaled(min: f64, max: f64, level: u8) -> f64 {
+    min + (max - min) * (level.clamp(1, 18) - 1) as f64 / 17.0
+}
+
+pub fn adaptive_physical(bonus_attack_damage: f64, ability_power: f64) -> bool {
+    0.35 * bonus_attack_damage >= 0.2 * ability_power
+}
+
+/** Splits adaptive force into attack damage and ability power, granting 0.6 AD for each point of AP */
+pub fn adaptive_split(bonus_attack_damage: f64, ability_power: f64, force: f64) -> (f64, f64) {
+    if adaptive_physical(bonus_attack_damage, ability_power) {
+        (0.6 * force, 0.0)
+    } else {
+        (0.0, force)
+    }
+}
+
+pub fn apply_adaptive(stats: &mut GameChampionStats, bonus_attack_damage: f64, force: f64) {
+    let (attack_damage, ability_power) =
+        adaptive_split(bonus_attack_damage, stats.ability_power, force);
+    stats.attack_damage += attack_damage;
+    stats.ability_power += ability_power;
+}
+
+pub fn adaptive_type(bonus_attack_damage: f64, ability_power: f64) -> String {
+    if adaptive_physical(bonus_attack_damage, ability_power) {
+        String::from("physical")
+    } else {
+        String::from("magic")
+    }
+}
+
+/** Adaptive force in AP units granted by rune stacks, with Gathering Storm falling back to game time */
+pub fn rune_adaptive(rune: &str, level: u8, requested: Option<u32>, minutes: f64) -> f64 {
+    match rune {
+        "8138" => 2.0 * requested.unwrap_or(0).min(10) as f64,
+        "8233" => level_scaled(3.0, 30.0, level),
+        "8236" => {
+            let intervals = requested.unwrap_or((minutes / 10.0).floor() as u32) as f64;
+            4.0 * intervals * (intervals + 1.0)
+        }
+        _ => 0.0,
+    }
+}
+
+/** Transcendence grants 5 ability haste at level 5 and 8 more at level 8 */
+pub fn rune_haste(rune: &str, level: u8) -> f64 {
+    match rune {
+        "8210" if level >= 8 => 13.0,
+        "8210" if level >= 5 => 5.0,
+        _ => 0.0,
+    }
+}
+
+pub fn is_shard(id: u32) -> bool {
+    (5001..=5013).contains(&id)
+}
+
+/** Live snapshots already include shards, so only synthesised stats need them */
+pub fn apply_shard(stats: &mut GameChampionStats, bonus_attack_damage: f64, id: u32, level: u8) {
+    match id {
+        5001 => {
+            let health = level_scaled(10.0, 180.0, level);
+            stats.max_health += health;
+            stats.current_health += health;
+        }
+        5011 => {
+            stats.max_health += 65.0;
+            stats.current_health += 65.0;
+        }
+        5002 => stats.armor += 6.0,
+        5003 => stats.magic_resist += 8.0,
+        5005 => stats.attack_speed *= 1.1,
+        5007 => stats.ability_haste += 8.0,
+        5008 => apply_adaptive(stats, bonus_attack_damage, 9.0),
+        _ => {}
+    }
+}
+
+/** Keystone stacks are opt-in, since live stats already carry any buff active in game */
+pub fn apply_keystones(
+    stats: &mut GameChampionStats,
+    bonus_attack_damage: f64,
+    runes: &[String],
+    stacks: &HashMap<String, u32>,
+    level: u8,
+) {
+    for rune in runes {
+        let count = match stacks.get(rune) {
+            Some(count) if *count > 0 => *count,
+            _ => continue,
+        };
+        match rune.as_str() {
+            "8010" => {
+                let force = level_scaled(1.8, 4.0, level) * count.min(CONQUEROR_MAX) as f64;
+                apply_adaptive(stats, bonus_attack_damage, force);
+            }
+            "8008" => {
+                let bonus = level_scaled(0.04, 0.12, level) * count.min(LETHAL_TEMPO_MAX) as f64;
+                stats.attack_speed *= 1.0 + bonus;
+            }
+            _ => continue,
+        }
+    }
+}
+
+pub fn keystone_modifier(runes: &[String], stacks: &HashMap<String, u32>) -> f64 {
+    let hits = stacks.get("8005").copied().unwrap_or(0);
+    if runes.iter().any(|rune| rune == "8005") && hits >= PRESS_THE_ATTACK_HITS {
+        PRESS_THE_ATTACK_EXPOSURE
+    } else {
+        0.0
+    }
+}
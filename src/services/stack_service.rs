@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use crate::structs::game_struct::{GameCoreStats, GameScores};
+
+pub struct StackRule {
+    pub stat: &'static str,
+    pub per_stack: f64,
+    pub max: Option<u32>,
+    pub kill: i32,
+    pub assist: i32,
+    pub death: i32,
+}
+
+/** Stacks gained per kill and assist and lost per death approximate the live client */
+pub fn stack_rule(item: &str) -> Option<StackRule> {
+    let rule = match item {
+        "1082" => StackRule {
+            stat: "abilityPower",
+            per_stack: 4.0,
+            max: Some(10),
+            kill: 2,
+            assist: 1,
+            death: -4,
+        },
+        "3041" => StackRule {
+            stat: "abilityPower",
+            per_stack: 5.0,
+            max: Some(25),
+            kill: 4,
+            assist: 2,
+            death: -10,
+        },
+        "6697" => StackRule {
+            stat: "attackDamage",
+            per_stack: 2.0,
+            max: None,
+            kill: 1,
+            assist: 1,
+            death: 0,
+        },
+        /* Heartsteel stacks are the permanent health gained, which scores cannot infer */
+        "3084" => StackRule {
+            stat: "maxHealth",
+            per_stack: 1.0,
+            max: None,
+            kill: 0,
+            assist: 0,
+            death: 0,
+        },
+        _ => return None,
+    };
+    Some(rule)
+}
+
+pub fn stack_count(item: &str, scores: &GameScores, requested: &HashMap<String, u32>) -> u32 {
+    let rule = match stack_rule(item) {
+        Some(rule) => rule,
+        None => return 0,
+    };
+    let count = requested.get(item).copied().unwrap_or_else(|| {
+        (scores.kills * rule.kill + scores.assists * rule.assist + scores.deaths * rule.death)
+            .max(0) as u32
+    });
+    rule.max.map_or(count, |max| count.min(max))
+}
+
+/** Items that keep the stacks of the component they are built from */
+fn inherited_from(item: &str) -> Option<&'static str> {
+    match item {
+        "3041" => Some("1082"),
+        _ => None,
+    }
+}
+
+/** Newly bought items start at the requested count or at the stacks carried over from an owned component, never from scores */
+pub fn bought_stacks(
+    item: &str,
+    owned: &[String],
+    scores: &GameScores,
+    requested: &HashMap<String, u32>,
+) -> u32 {
+    let rule = match stack_rule(item) {
+        Some(rule) => rule,
+        None => return 0,
+    };
+    let count = requested.get(item).copied().unwrap_or_else(|| {
+        inherited_from(item)
+            .filter(|component| owned.iter().any(|id| id == component))
+            .map_or(0, |component| stack_count(component, scores, requested))
+    });
+    rule.max.map_or(count, |max| count.min(max))
+}
+
+pub fn stack_bonus(item: &str, stacks: u32) -> Option<(&'static str, f64)> {
+    let rule = stack_rule(item)?;
+    if stacks == 0 {
+        return None;
+    }
+    Some((rule.stat, rule.per_stack * stacks as f64))
+}
+
+pub fn apply_core(core: &mut GameCoreStats, stat: &str, value: f64) {
+    match stat {
+        "abilityPower" => core.ability_power += value,
+        "attackDamage" => core.attack_damage += value,
+        "maxHealth" => core.max_health += value,
+        "armor" => core.armor += value,
+        "magicResist" => core.magic_resist += value,
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{bought_stacks, stack_count};
+    use crate::structs::game_struct::GameScores;
+
+    fn scores(kills: i32, deaths: i32, assists: i32) -> GameScores {
+        GameScores {
+            kills,
+            deaths,
+            assists,
+        }
+    }
+
+    #[test]
+    fn stack_count_infers_stacks_from_scores() {
+        let requested = HashMap::new();
+        assert_eq!(stack_count("1082", &scores(3, 0, 2), &requested), 8);
+        assert_eq!(stack_count("1082", &scores(10, 0, 0), &requested), 10);
+        assert_eq!(stack_count("1082", &scores(1, 3, 0), &requested), 0);
+    }
+
+    #[test]
+    fn stack_count_prefers_requested_stacks_within_the_cap() {
+        let requested = HashMap::from([("3041".to_owned(), 12), ("1082".to_owned(), 40)]);
+        assert_eq!(stack_count("3041", &scores(0, 0, 0), &requested), 12);
+        assert_eq!(stack_count("1082", &scores(0, 0, 0), &requested), 10);
+    }
+
+    #[test]
+    fn stack_count_ignores_items_without_stacks() {
+        assert_eq!(stack_count("3031", &scores(5, 0, 5), &HashMap::new()), 0);
+    }
+
+    #[test]
+    fn bought_stacks_ignore_scores() {
+        let owned = vec!["3031".to_owned()];
+        assert_eq!(
+            bought_stacks("3041", &owned, &scores(5, 0, 5), &HashMap::new()),
+            0
+        );
+        let requested = HashMap::from([("3041".to_owned(), 7)]);
+        assert_eq!(
+            bought_stacks("3041", &owned, &scores(5, 0, 5), &requested),
+            7
+        );
+    }
+
+    #[test]
+    fn bought_stacks_carry_over_from_an_owned_dark_seal() {
+        let owned = vec!["1082".to_owned()];
+        assert_eq!(
+            bought_stacks("3041", &owned, &scores(3, 0, 2), &HashMap::new()),
+            8
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use super::combo_service::{
+    auto_attack_damage, combo_damage, damage_entry, is_sustain, percent_ratio,
+};
+use super::rune_service::CONQUEROR_MAX;
+use crate::structs::game_struct::{
+    GameChampionStats, GameDamageReturn, GamePlayerDamage, GamePlayerDamages, GamePlayerSustain,
+    GameSurvivability,
+};
+
+static GRIEVOUS_ITEMS: [&str; 8] = [
+    "3011", "3033", "3075", "3076", "3123", "3165", "3916", "6609",
+];
+
+pub fn effective_health(health: f64, resist: f64) -> f64 {
+    if resist >= 0.0 {
+        health * (1.0 + resist / 100.0)
+    } else {
+        health / (2.0 - 100.0 / (100.0 - resist))
+    }
+}
+
+fn combos_to_kill(health: f64, combo: f64) -> Option<f64> {
+    if combo > 0.0 {
+        Some(health / combo)
+    } else {
+        None
+    }
+}
+
+pub fn survivability(stats: &GameChampionStats, threats: &[(String, f64)]) -> GameSurvivability {
+    let health = stats.current_health;
+    let team: f64 = threats.iter().map(|(_, combo)| combo).sum();
+
+    GameSurvivability {
+        health,
+        physical: effective_health(health, stats.armor),
+        magic: effective_health(health, stats.magic_resist),
+        combos_to_kill: threats
+            .iter()
+            .filter_map(|(name, combo)| combos_to_kill(health, *combo).map(|c| (name.clone(), c)))
+            .collect(),
+        team_combos_to_kill: combos_to_kill(health, team),
+    }
+}
+
+pub fn survivability_change(
+    max: &GameSurvivability,
+    curr: &GameSurvivability,
+) -> GameSurvivability {
+    let mut combos = HashMap::with_capacity(max.combos_to_kill.len());
+    for (name, next) in max.combos_to_kill.iter() {
+        if let Some(prev) = curr.combos_to_kill.get(name) {
+            combos.insert(name.clone(), next - prev);
+        }
+    }
+
+    GameSurvivability {
+        health: max.health - curr.health,
+        physical: max.physical - curr.physical,
+        magic: max.magic - curr.magic,
+        combos_to_kill: combos,
+        team_combos_to_kill: match (max.team_combos_to_kill, curr.team_combos_to_kill) {
+            (Some(next), Some(prev)) => Some(next - prev),
+            _ => None,
+        },
+    }
+}
+
+fn sustain_source(name: &str, min: f64) -> GamePlayerDamage {
+    GamePlayerDamage {
+        min,
+        max: None,
+        damage_type: String::from("heal"),
+        name: Some(name.to_owned()),
+        ..Default::default()
+    }
+}
+
+pub fn sustain(
+    damages: &GamePlayerDamages,
+    keys: &[String],
+    stats: &GameChampionStats,
+    runes: &[String],
+    enemy_items: &[String],
+    stacks: &HashMap<String, u32>,
+) -> GamePlayerSustain {
+    let mut sources = GameDamageReturn::with_capacity(8);
+
+    let combo = combo_damage(damages, keys);
+    let physical: f64 = keys
+        .iter()
+        .filter_map(|key| damage_entry(damages, key))
+        .filter(|damage| damage.damage_type == "physical")
+        .map(|damage| damage.min)
+        .sum();
+    let autos = keys.iter().filter(|key| key.as_str() == "A").count() as f64;
+
+    let mut vamp = vec![
+        (
+            "lifeSteal",
+            "Life Steal",
+            auto_attack_damage(damages, stats) * percent_ratio(stats.life_steal) * autos,
+        ),
+        (
+            "physicalVamp",
+            "Physical Vamp",
+            physical * percent_ratio(stats.physical_vamp),
+        ),
+        (
+            "omnivamp",
+            "Omnivamp",
+            combo * percent_ratio(stats.omnivamp),
+        ),
+    ];
+    /* Conqueror only heals once fully stacked */
+    let conqueror = stacks.get("8010").copied().unwrap_or(0) >= CONQUEROR_MAX;
+    if conqueror && runes.contains(&"8010".to_owned()) {
+        let ratio = if stats.attack_range > 350.0 {
+            0.05
+        } else {
+            0.08
+        };
+        vamp.push(("8010", "Conqueror", combo * ratio));
+    }
+    for (key, name, value) in vamp {
+        if value > 0.0 {
+            sources.insert(key.to_owned(), sustain_source(name, value));
+        }
+    }
+
+    for key in keys {
+        if let Some(damage) = damage_entry(damages, key).filter(|d| is_sustain(d)) {
+            sources.insert(key.clone(), damage.clone());
+        }
+    }
+
+    let grievous = enemy_items
+        .iter()
+        .any(|item| GRIEVOUS_ITEMS.contains(&item.as_str()));
+    if grievous {
+        for source in sources.values_mut() {
+            if source.damage_type == "heal" {
+                source.min *= 0.6;
+                source.max = source.max.map(|max| max * 0.6);
+            }
+        }
+    }
+
+    let total = |damage_type: &str| -> f64 {
+        sources
+            .values()
+            .filter(|source| source.damage_type == damage_type)
+            .map(|source| source.min)
+            .sum()
+    };
+
+    GamePlayerSustain {
+        heal: total("heal"),
+        shield: total("shield"),
+        sources,
+        grievous,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::effective_health;
+
+    #[test]
+    fn effective_health_scales_with_resists() {
+        assert_eq!(effective_health(1000.0, 0.0), 1000.0);
+        assert_eq!(effective_health(1000.0, 100.0), 2000.0);
+    }
+
+    #[test]
+    fn effective_health_drops_below_health_with_negative_resists() {
+        let health = effective_health(1000.0, -25.0);
+        assert!((health - 1000.0 / 1.2).abs() < 1e-9);
+        assert!(effective_health(1000.0, -100.0) < health);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::structs::game_struct::{GameCoreStats, GameTargetProfile};
+
+pub static SMITE_DAMAGE: f64 = 600.0;
+
+/** Baron's Gaze halves damage from champions it has hit, which covers any trade at Baron */
+fn damage_modifier(id: &str) -> f64 {
+    match id {
+        "Baron" => 0.5,
+        _ => 1.0,
+    }
+}
+
+fn profile(
+    id: &str,
+    name: &str,
+    max_health: f64,
+    armor: f64,
+    magic_resist: f64,
+    abilities: bool,
+    smite: bool,
+) -> GameTargetProfile {
+    GameTargetProfile {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        stats: GameCoreStats {
+            max_health,
+            armor,
+            magic_resist,
+            ..Default::default()
+        },
+        damage_mod: damage_modifier(id),
+        abilities,
+        smite,
+        current_health: None,
+        items: Vec::new(),
+    }
+}
+
+/** Values approximate the live client and scale linearly with game time */
+pub fn target_profile(id: &str, game_time: f64) -> Option<GameTargetProfile> {
+    let minutes = game_time / 60.0;
+    let upgrades = ((game_time - 90.0) / 90.0).floor().max(0.0);
+    let camp = (1.0 + 0.035 * minutes).min(2.5);
+
+    let target = match id {
+        "TargetDummy" => profile(id, "Target Dummy", 1000.0, 0.0, 0.0, true, false),
+        "Baron" => profile(
+            id,
+            "Baron Nashor",
+            9000.0 + 180.0 * (minutes - 20.0).max(0.0),
+            120.0,
+            70.0,
+            true,
+            true,
+        ),
+        "Elder" => profile(
+            id,
+            "Elder Dragon",
+            6400.0 + 180.0 * (minutes - 35.0).max(0.0),
+            120.0,
+            70.0,
+            true,
+            true,
+        ),
+        "Dragon" => profile(
+            id,
+            "Elemental Drake",
+            3500.0 + 60.0 * (minutes - 5.0).max(0.0),
+            21.0,
+            30.0,
+            true,
+            true,
+        ),
+        "Herald" => profile(
+            id,
+            "Rift Herald",
+            5500.0 + 95.0 * (minutes - 14.0).max(0.0),
+            60.0,
+            50.0,
+            true,
+            true,
+        ),
+        "Gromp" => profile(id, "Gromp", 2050.0 * camp, 27.0, -15.0, true, true),
+        "Blue" => profile(id, "Blue Sentinel", 2300.0 * camp, 10.0, -15.0, true, true),
+        "Red" => profile(
+            id,
+            "Red Brambleback",
+            2300.0 * camp,
+            10.0,
+            -15.0,
+            true,
+            true,
+        ),
+        "Krugs" => profile(id, "Ancient Krug", 1350.0 * camp, 37.0, -15.0, true, true),
+        "Wolves" => profile(
+            id,
+            "Greater Murk Wolf",
+            1600.0 * camp,
+            15.0,
+            -15.0,
+            true,
+            true,
+        ),
+        "Raptors" => profile(id, "Crimson Raptor", 1200.0 * camp, 20.0, -15.0, true, true),
+        "Scuttle" => profile(id, "Rift Scuttler", 1050.0 * camp, 42.0, 42.0, true, true),
+        "MeleeMinion" => profile(
+            id,
+            "Melee Minion",
+            477.0 + 22.0 * upgrades,
+            0.0,
+            0.0,
+            true,
+            true,
+        ),
+        "CasterMinion" => profile(
+            id,
+            "Caster Minion",
+            296.0 + 8.0 * upgrades,
+            0.0,
+            0.0,
+            true,
+            true,
+        ),
+        "SiegeMinion" => profile(
+            id,
+            "Siege Minion",
+            912.0 + 25.0 * upgrades,
+            0.0,
+            0.0,
+            true,
+            true,
+        ),
+        "SuperMinion" => profile(
+            id,
+            "Super Minion",
+            1600.0 + 200.0 * upgrades,
+            100.0,
+            -30.0,
+            true,
+            true,
+        ),
+        "OuterTurret" => profile(id, "Outer Turret", 5000.0, 40.0, 40.0, false, false),
+        "InnerTurret" => profile(id, "Inner Turret", 3600.0, 55.0, 55.0, false, false),
+        "InhibitorTurret" => profile(id, "Inhibitor Turret", 3300.0, 70.0, 70.0, false, false),
+        "NexusTurret" => profile(id, "Nexus Turret", 2700.0, 70.0, 70.0, false, false),
+        _ => return None,
+    };
+
+    Some(target)
+}
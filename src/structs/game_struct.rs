@@ -7,20 +7,20 @@ use crate::structs::target_struct::RiotChampionTarget;
 
 use super::riot_champion_struct::RiotChampionStats;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GamePassive {
     pub display_name: String,
     pub id: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GameAbility {
     pub ability_level: u8,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "PascalCase")]
 pub struct GameAbilities {
     pub passive: GamePassive,
@@ -30,6 +30,31 @@ pub struct GameAbilities {
     pub r: GameAbility,
 }
 
+impl GameAbilities {
+    pub fn from_ranks(passive: &str, ranks: [u8; 4]) -> Self {
+        let [q, w, e, r] = ranks;
+        Self {
+            passive: GamePassive {
+                display_name: passive.to_owned(),
+                id: String::new(),
+            },
+            q: GameAbility { ability_level: q },
+            w: GameAbility { ability_level: w },
+            e: GameAbility { ability_level: e },
+            r: GameAbility { ability_level: r },
+        }
+    }
+    pub fn rank(&self, key: &str) -> Option<u8> {
+        match key.chars().next() {
+            Some('Q') => Some(self.q.ability_level),
+            Some('W') => Some(self.w.ability_level),
+            Some('E') => Some(self.e.ability_level),
+            Some('R') => Some(self.r.ability_level),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GameChampionStats {
@@ -39,6 +64,8 @@ pub struct GameChampionStats {
     pub armor_penetration_percent: f64,
     pub attack_damage: f64,
     pub attack_range: f64,
+    #[serde(default)]
+    pub attack_speed: f64,
     pub crit_chance: f64,
     pub crit_damage: f64,
     pub current_health: f64,
@@ -47,17 +74,58 @@ pub struct GameChampionStats {
     pub magic_resist: f64,
     pub max_health: f64,
     pub physical_lethality: f64,
+    #[serde(default)]
+    pub life_steal: f64,
+    #[serde(default)]
+    pub omnivamp: f64,
+    #[serde(default)]
+    pub physical_vamp: f64,
+    #[serde(default)]
+    pub heal_shield_power: f64,
+    #[serde(default)]
+    pub ability_haste: f64,
     pub resource_max: f64,
+    pub resource_type: Option<String>,
+    pub resource_value: Option<f64>,
 }
 
 impl GameChampionStats {
+    pub fn from_core(core: &GameCoreStats, stats: &RiotChampionStats, level: u8) -> Self {
+        Self {
+            ability_power: core.ability_power,
+            armor: core.armor,
+            armor_penetration_percent: 1.0,
+            attack_damage: core.attack_damage,
+            attack_range: stats.attackrange,
+            attack_speed: stats.attackspeed
+                * (1.0
+                    + GameCoreStats::formula(0.0, stats.attackspeedperlevel / 100.0, level as f64)),
+            crit_damage: 175.0,
+            current_health: core.max_health,
+            magic_penetration_percent: 1.0,
+            magic_resist: core.magic_resist,
+            max_health: core.max_health,
+            resource_max: core.resource_max,
+            ..Default::default()
+        }
+    }
+    pub fn apply_delta(&mut self, next: &GameCoreStats, curr: &GameCoreStats) {
+        let health = next.max_health - curr.max_health;
+        self.max_health += health;
+        self.current_health = (self.current_health + health).max(0.0);
+        self.armor += next.armor - curr.armor;
+        self.magic_resist += next.magic_resist - curr.magic_resist;
+        self.attack_damage += next.attack_damage - curr.attack_damage;
+        self.resource_max += next.resource_max - curr.resource_max;
+        self.ability_power += next.ability_power - curr.ability_power;
+    }
     fn to_camel_case(snake_str: &str) -> String {
-        let mut s = snake_str.split('_').peekable();
+        let mut s = snake_str.split('_');
         let mut camel_case = String::new();
         if let Some(first) = s.next() {
             camel_case.push_str(first);
         }
-        while let Some(word) = s.next() {
+        for word in s {
             camel_case.push_str(&Self::capitalize(word));
         }
         camel_case
@@ -69,7 +137,7 @@ impl GameChampionStats {
             Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
         }
     }
-    pub fn into_hashmap_camel(&mut self) -> HashMap<String, f64> {
+    pub fn to_hashmap_camel(&self) -> HashMap<String, f64> {
         let json = serde_json::to_value(self).unwrap();
         let mut map = HashMap::new();
 
@@ -95,6 +163,7 @@ impl GameChampionStats {
                 "armorPenetrationPercent" => stats.armor_penetration_percent = value,
                 "attackDamage" => stats.attack_damage = value,
                 "attackRange" => stats.attack_range = value,
+                "attackSpeed" => stats.attack_speed = value,
                 "critChance" => stats.crit_chance = value,
                 "critDamage" => stats.crit_damage = value,
                 "currentHealth" => stats.current_health = value,
@@ -103,7 +172,13 @@ impl GameChampionStats {
                 "magicResist" => stats.magic_resist = value,
                 "maxHealth" => stats.max_health = value,
                 "physicalLethality" => stats.physical_lethality = value,
+                "lifeSteal" => stats.life_steal = value,
+                "omnivamp" => stats.omnivamp = value,
+                "physicalVamp" => stats.physical_vamp = value,
+                "healShieldPower" => stats.heal_shield_power = value,
+                "abilityHaste" => stats.ability_haste = value,
                 "resourceMax" => stats.resource_max = value,
+                "resourceValue" => stats.resource_value = Some(value),
                 _ => {}
             }
         }
@@ -118,7 +193,7 @@ pub struct GameRuneProp {
     pub id: u32,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GameFullRunes {
     pub general_runes: Vec<GameRuneProp>,
@@ -165,11 +240,11 @@ impl GameCoreStats {
 impl GameChampionStats {
     pub fn bonus_stats(&self, current: GameCoreStats) -> GameCoreStats {
         GameCoreStats {
-            max_health: current.max_health - self.max_health,
-            armor: current.armor - self.armor,
-            magic_resist: current.magic_resist - self.magic_resist,
-            attack_damage: current.attack_damage - self.attack_damage,
-            resource_max: current.resource_max - self.resource_max,
+            max_health: self.max_health - current.max_health,
+            armor: self.armor - current.armor,
+            magic_resist: self.magic_resist - current.magic_resist,
+            attack_damage: self.attack_damage - current.attack_damage,
+            resource_max: self.resource_max - current.resource_max,
             ability_power: self.ability_power,
         }
     }
@@ -190,6 +265,22 @@ pub struct GameRelevant {
     pub spell: GameRelevantProps,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSurvivability {
+    pub health: f64,
+    pub physical: f64,
+    pub magic: f64,
+    pub combos_to_kill: HashMap<String, f64>,
+    pub team_combos_to_kill: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GameSurvivabilityTool {
+    pub dif: GameSurvivability,
+    pub max: GameSurvivability,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GameToolInfo {
     pub id: String,
@@ -197,6 +288,7 @@ pub struct GameToolInfo {
     pub active: bool,
     pub gold: Option<u32>,
     pub raw: HashMap<String, Value>,
+    pub survivability: Option<GameSurvivabilityTool>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -208,6 +300,164 @@ pub struct GameDragonProps {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
+pub struct GameResource {
+    pub resource_type: String,
+    pub current: f64,
+    pub max: f64,
+    pub costs: HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct GameCalculateOptions {
+    pub teamfight: Option<GameTeamfightRequest>,
+    #[serde(default)]
+    pub targets: Vec<String>,
+    pub dummy: Option<GameDummyRequest>,
+    pub overrides: Option<GameOverrides>,
+    #[serde(default)]
+    pub compare: Vec<String>,
+    pub set: Option<GameToolSet>,
+    /** Stack counts keyed by item or keystone id, overriding those inferred from scores */
+    #[serde(default)]
+    pub stacks: HashMap<String, u32>,
+    /** Values for the named parameters declared by the champion's ability formulas */
+    #[serde(default)]
+    pub params: HashMap<String, f64>,
+    /** Active form for multi-form champions, inferred from attack range when missing */
+    pub form: Option<String>,
+    /** Overrides the mode inferred from the map, e.g. URF on Summoner's Rift */
+    pub mode: Option<String>,
+    /** Only applied in Arena */
+    pub augments: Option<GameAugments>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct GameAugments {
+    /** Stat bonuses keyed like `GameChampionStats`, e.g. `abilityPower` */
+    #[serde(default)]
+    pub stats: HashMap<String, f64>,
+    /** Extra damage dealt as a ratio, e.g. 0.1 for 10% */
+    #[serde(default)]
+    pub damage: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameMode {
+    pub name: String,
+    pub map_number: u8,
+    pub damage_dealt: f64,
+    pub ability_haste: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct GameToolSet {
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub sell: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GameToolCompare {
+    pub id: String,
+    pub name: String,
+    pub gold: u32,
+    pub sum: f64,
+    pub dps: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GameOverrides {
+    pub level: Option<u8>,
+    pub abilities: Option<[u8; 4]>,
+    pub items: Option<Vec<u32>>,
+    pub runes: Option<Vec<u32>>,
+    /** Item ids keyed by enemy summoner name */
+    #[serde(default)]
+    pub enemies: HashMap<String, Vec<u32>>,
+}
+
+impl GameOverrides {
+    pub fn is_valid(&self) -> bool {
+        self.level.is_none_or(valid_level)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GameWhatIf {
+    pub original: GamePlayerDamages,
+    pub dif: GamePlayerDamages,
+    pub sum: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameTargetProfile {
+    pub id: String,
+    pub name: String,
+    pub stats: GameCoreStats,
+    pub damage_mod: f64,
+    pub abilities: bool,
+    pub smite: bool,
+    pub current_health: Option<f64>,
+    #[serde(default)]
+    pub items: Vec<GamePlayerItems>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameDummyRequest {
+    pub max_health: f64,
+    pub current_health: Option<f64>,
+    pub armor: f64,
+    pub magic_resist: f64,
+    #[serde(default)]
+    pub items: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameTargetDamage {
+    pub id: String,
+    pub name: String,
+    pub health: f64,
+    pub damage: GamePlayerDamages,
+    pub combo: f64,
+    pub smite: Option<f64>,
+    pub secure_health: f64,
+    pub autos_to_kill: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct GameTeamfightRequest {
+    pub target: Option<String>,
+    pub combo: Option<Vec<String>>,
+    #[serde(default)]
+    pub hits: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GameTeamfight {
+    pub target: String,
+    pub keys: Vec<String>,
+    pub total: f64,
+    pub damage: HashMap<String, f64>,
+    pub kills: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameAllySupport {
+    pub summoner_name: String,
+    pub champion_name: String,
+    pub abilities: GameDamageReturn,
+    pub tool: Option<GameDamageReturn>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct GameActivePlayer {
     pub summoner_name: String,
     pub level: u8,
@@ -224,9 +474,23 @@ pub struct GameActivePlayer {
     pub skin: Option<u8>,
     pub tool: Option<GameToolInfo>,
     pub relevant: Option<GameRelevant>,
+    pub resource: Option<GameResource>,
+    pub survivability: Option<GameSurvivability>,
+    pub teamfight: Option<GameTeamfight>,
+    pub allies: Option<Vec<GameAllySupport>>,
+    pub targets: Option<Vec<GameTargetDamage>>,
+    pub compare: Option<Vec<GameToolCompare>>,
+    #[serde(default)]
+    pub params: HashMap<String, f64>,
+    pub form: Option<String>,
+    pub cooldowns: Option<HashMap<String, f64>>,
+    #[serde(default)]
+    pub stacks: HashMap<String, u32>,
+    pub adaptive: Option<String>,
+    pub mode: Option<GameMode>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GameScores {
     pub assists: i32,
@@ -234,14 +498,14 @@ pub struct GameScores {
     pub deaths: i32,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GameSummonerSpell {
     pub display_name: String,
     pub raw_description: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GameSummonerSpells {
     pub summoner_spell_one: GameSummonerSpell,
@@ -265,6 +529,11 @@ pub struct GamePlayerDamage {
     pub name: Option<String>,
     pub area: Option<bool>,
     pub onhit: Option<bool>,
+    pub active: Option<bool>,
+    /** Damage per second of cooldown */
+    pub per_second: Option<f64>,
+    /** Damage per unit of the resource spent */
+    pub per_mana: Option<f64>,
 }
 
 impl GamePlayerDamage {
@@ -290,7 +559,7 @@ pub struct GamePlayerDamages {
 }
 
 impl GamePlayerDamages {
-    pub fn into_hashmap(&self) -> HashMap<&'static str, &GameDamageReturn> {
+    pub fn to_hashmap(&self) -> HashMap<&'static str, &GameDamageReturn> {
         let mut map = HashMap::new();
         map.insert("abilities", &self.abilities);
         map.insert("items", &self.items);
@@ -306,10 +575,52 @@ pub struct GamePlayerTool {
     pub max: GamePlayerDamages,
     pub sum: f64,
     pub rec: Option<HashMap<String, f64>>,
+    /** Change in seconds for each cooldown, negative when the tool shortens it */
+    pub cooldowns: Option<HashMap<String, f64>>,
+    /** Change in summed damage per second of cooldown, which also values haste */
+    pub dps: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GameCombo {
+    pub name: String,
+    pub keys: Vec<String>,
+    pub damage: f64,
+    pub cost: f64,
+    pub affordable: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
+pub struct GameKillReport {
+    pub combo: Option<String>,
+    pub burst: f64,
+    pub dps: f64,
+    pub time_to_kill: Option<f64>,
+    pub lethal_health: f64,
+    pub lethal_percent: f64,
+    pub autos_after_combo: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GamePlayerThreat {
+    pub damage: GamePlayerDamages,
+    pub combo: f64,
+    pub percent: f64,
+    pub tool_combo: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GamePlayerSustain {
+    pub sources: GameDamageReturn,
+    pub heal: f64,
+    pub shield: f64,
+    pub grievous: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct GamePlayer {
     pub champion_name: String,
     pub level: u8,
@@ -329,6 +640,96 @@ pub struct GamePlayer {
     pub champion_stats: Option<GameCoreStats>,
     pub damage: Option<GamePlayerDamages>,
     pub tool: Option<GamePlayerTool>,
+    pub combos: Option<Vec<GameCombo>>,
+    pub kill: Option<GameKillReport>,
+    pub threat: Option<GamePlayerThreat>,
+    pub sustain: Option<GamePlayerSustain>,
+    pub current_health: Option<f64>,
+    pub what_if: Option<GameWhatIf>,
+    pub compare: Option<HashMap<String, GamePlayerTool>>,
+    pub forms: Option<HashMap<String, GameDamageReturn>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameMatrixCell {
+    pub summoner_name: String,
+    pub champion_name: String,
+    pub combo: f64,
+    pub dps: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameMatrixRow {
+    pub summoner_name: String,
+    pub champion_name: String,
+    pub team: String,
+    pub targets: Vec<GameMatrixCell>,
+}
+
+pub type GameDamageMatrix = Vec<GameMatrixRow>;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSandboxEnemy {
+    pub champion: String,
+    pub level: u8,
+    #[serde(default)]
+    pub items: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSandboxRequest {
+    pub champion: String,
+    pub level: u8,
+    pub abilities: Option<[u8; 4]>,
+    #[serde(default)]
+    pub items: Vec<u32>,
+    #[serde(default)]
+    pub runes: Vec<u32>,
+    #[serde(default)]
+    pub spells: Vec<String>,
+    #[serde(default)]
+    pub enemies: Vec<GameSandboxEnemy>,
+    pub game_time: Option<f64>,
+    pub item: String,
+    #[serde(flatten)]
+    pub options: GameCalculateOptions,
+}
+
+impl GameSandboxRequest {
+    pub fn is_valid(&self) -> bool {
+        valid_level(self.level)
+            && self.enemies.iter().all(|enemy| valid_level(enemy.level))
+            && self
+                .abilities
+                .is_none_or(|ranks| valid_ranks(ranks, self.level))
+    }
+}
+
+pub fn valid_level(level: u8) -> bool {
+    (1..=18).contains(&level)
+}
+
+/** Highest rank reachable at `level`: basic abilities gain one rank every two levels up to 5, ultimates unlock at 6, 11 and 16 */
+pub fn rank_caps(level: u8) -> [u8; 4] {
+    let basic = level.div_ceil(2).min(5);
+    let ultimate = match level {
+        16.. => 3,
+        11.. => 2,
+        6.. => 1,
+        _ => 0,
+    };
+    [basic, basic, basic, ultimate]
+}
+
+/** One point is spent per level and no ability may pass its cap from `rank_caps` */
+pub fn valid_ranks(ranks: [u8; 4], level: u8) -> bool {
+    let caps = rank_caps(level);
+    ranks.iter().zip(caps.iter()).all(|(rank, cap)| rank <= cap)
+        && ranks.iter().map(|rank| *rank as u16).sum::<u16>() <= level as u16
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
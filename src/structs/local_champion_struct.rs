@@ -11,4 +11,176 @@ pub struct LocalChampionAbility {
     pub area: Option<bool>,
     pub min: Vec<String>,
     pub max: Option<Vec<String>>,
+    pub cost: Option<Vec<f64>>,
+    pub target: Option<String>,
+    /** Named formula parameters and their default values, e.g. stacks or empowered casts */
+    #[serde(default)]
+    pub params: HashMap<String, f64>,
+    /** Abilities tagged with a form only apply while the champion is in that form */
+    pub form: Option<String>,
+    /** Levels at which each passive entry starts to apply, for passives scaling on breakpoints */
+    pub breakpoints: Option<Vec<u8>>,
+}
+
+impl LocalChampionAbility {
+    pub fn targets_ally(&self) -> bool {
+        self.target.as_deref() == Some("ally")
+    }
+    pub fn in_form(&self, form: Option<&str>) -> bool {
+        self.form.is_none() || self.form.as_deref() == form
+    }
+    /** Passives hold one entry per level, ultimates three ranks and basic abilities five */
+    pub fn expected_len(&self, key: &str) -> usize {
+        match key.chars().next() {
+            Some('P') => self.breakpoints.as_ref().map_or(18, Vec::len),
+            Some('R') => 3,
+            _ => 5,
+        }
+    }
+    /** Index of the entry for a level, stepping through breakpoints when they are set */
+    pub fn level_index(&self, level: u8) -> usize {
+        match &self.breakpoints {
+            Some(breakpoints) => breakpoints.iter().rposition(|at| *at <= level).unwrap_or(0),
+            None => level.clamp(1, 18) as usize - 1,
+        }
+    }
+    pub fn validate(&self, key: &str) -> Vec<String> {
+        let mut issues = Vec::new();
+        let expected = self.expected_len(key);
+        if let Some(breakpoints) = &self.breakpoints {
+            if !key.starts_with('P') {
+                issues.push(format!("{}: breakpoints are only used by passives", key));
+            }
+            if breakpoints.first() != Some(&1) || breakpoints.windows(2).any(|w| w[0] >= w[1]) {
+                issues.push(format!("{}: breakpoints must start at 1 and increase", key));
+            }
+        }
+        if self.min.is_empty() {
+            issues.push(format!("{}: missing min entries", key));
+        } else if self.min.len() < expected {
+            issues.push(format!(
+                "{}: min has {} of {} entries",
+                key,
+                self.min.len(),
+                expected
+            ));
+        }
+        if let Some(max) = self.max.as_ref().filter(|max| max.len() < expected) {
+            issues.push(format!(
+                "{}: max has {} of {} entries",
+                key,
+                max.len(),
+                expected
+            ));
+        }
+        issues
+    }
+    /** Repeats the last entry so short arrays can still be indexed by level or rank */
+    pub fn fill_missing(&mut self, key: &str) {
+        let expected = self.expected_len(key);
+        let fill = |entries: &mut Vec<String>| {
+            if let Some(last) = entries.last().cloned() {
+                entries.resize(expected.max(entries.len()), last);
+            }
+        };
+        fill(&mut self.min);
+        if let Some(max) = self.max.as_mut() {
+            fill(max);
+        }
+    }
+    pub fn resolve_params(&self, values: &HashMap<String, f64>) -> HashMap<String, f64> {
+        self.params
+            .iter()
+            .map(|(key, default)| (key.clone(), *values.get(key).unwrap_or(default)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::LocalChampionAbility;
+
+    fn ability(
+        min: usize,
+        max: Option<usize>,
+        breakpoints: Option<Vec<u8>>,
+    ) -> LocalChampionAbility {
+        let entries = |len: usize| (0..len).map(|i| i.to_string()).collect::<Vec<_>>();
+        serde_json::from_value(json!({
+            "type": "magic",
+            "area": null,
+            "min": entries(min),
+            "max": max.map(entries),
+            "cost": null,
+            "target": null,
+            "form": null,
+            "breakpoints": breakpoints,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn level_index_clamps_levels_without_breakpoints() {
+        let passive = ability(18, None, None);
+        assert_eq!(passive.level_index(0), 0);
+        assert_eq!(passive.level_index(1), 0);
+        assert_eq!(passive.level_index(18), 17);
+        assert_eq!(passive.level_index(20), 17);
+    }
+
+    #[test]
+    fn level_index_steps_through_breakpoints() {
+        let passive = ability(3, None, Some(vec![1, 6, 11]));
+        assert_eq!(passive.level_index(5), 0);
+        assert_eq!(passive.level_index(6), 1);
+        assert_eq!(passive.level_index(18), 2);
+    }
+
+    #[test]
+    fn validate_accepts_complete_arrays() {
+        assert!(ability(18, Some(18), None).validate("P").is_empty());
+        assert!(ability(3, None, Some(vec![1, 6, 11]))
+            .validate("P")
+            .is_empty());
+        assert!(ability(5, Some(5), None).validate("Q").is_empty());
+        assert!(ability(3, None, None).validate("R").is_empty());
+    }
+
+    #[test]
+    fn validate_reports_short_and_malformed_arrays() {
+        assert_eq!(
+            ability(3, None, None).validate("P"),
+            vec!["P: min has 3 of 18 entries"]
+        );
+        assert_eq!(
+            ability(5, Some(2), None).validate("W"),
+            vec!["W: max has 2 of 5 entries"]
+        );
+        assert_eq!(
+            ability(0, None, None).validate("E"),
+            vec!["E: missing min entries"]
+        );
+        assert_eq!(
+            ability(2, None, Some(vec![6, 1])).validate("P"),
+            vec!["P: breakpoints must start at 1 and increase"]
+        );
+        assert_eq!(
+            ability(5, None, Some(vec![1])).validate("Q"),
+            vec!["Q: breakpoints are only used by passives"]
+        );
+    }
+
+    #[test]
+    fn fill_missing_repeats_the_last_entry() {
+        let mut basic = ability(2, Some(1), None);
+        basic.fill_missing("Q");
+        assert_eq!(basic.min, vec!["0", "1", "1", "1", "1"]);
+        assert_eq!(basic.max.unwrap(), vec!["0", "0", "0", "0", "0"]);
+
+        let mut empty = ability(0, None, None);
+        empty.fill_missing("P");
+        assert!(empty.min.is_empty());
+    }
 }
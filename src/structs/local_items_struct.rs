@@ -23,6 +23,10 @@ pub struct LocalItemData {
     pub onhit: bool,
     pub effect: Option<Vec<f64>>,
     pub damage: Option<DamageRange>,
+    /** Actives are cast like abilities instead of proccing on their own */
+    #[serde(default)]
+    pub active: bool,
+    pub cooldown: Option<f64>,
 }
 
 #[derive(Debug, Deserialize, Clone, Serialize)]
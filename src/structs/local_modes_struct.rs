@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+fn unchanged() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Serialize)]
+pub struct LocalModeBalance {
+    #[serde(default = "unchanged")]
+    pub dealt: f64,
+    #[serde(default = "unchanged")]
+    pub taken: f64,
+}
+
+/** Balance modifiers keyed by mode and then by champion id */
+pub type LocalModes = HashMap<String, HashMap<String, LocalModeBalance>>;
@@ -1,9 +1,9 @@
 pub mod game_struct;
 pub mod local_champion_struct;
 pub mod local_items_struct;
+pub mod local_modes_struct;
 pub mod local_runes_struct;
 pub mod local_stats_struct;
-pub mod riot_allchampion_struct;
 pub mod riot_champion_struct;
 pub mod riot_items_struct;
 pub mod routes_struct;
@@ -1,6 +1,8 @@
 use sea_orm::prelude::DateTimeWithTimeZone;
 use serde::{Deserialize, Serialize};
 
+use super::game_struct::{GameCalculateOptions, GameDamageMatrix, GameProps};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LastByCodeResponseData {
     pub game_id: String,
@@ -28,4 +30,39 @@ pub struct LastByCodeRequest {
     pub code: String,
     pub item: String,
     pub rec: bool,
+    #[serde(flatten)]
+    pub options: GameCalculateOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatrixByCodeRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatrixByCodeResponseData {
+    pub game_id: String,
+    pub created_at: DateTimeWithTimeZone,
+    pub game_code: Option<String>,
+    pub matrix: GameDamageMatrix,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatrixByCodeResponse {
+    pub success: bool,
+    pub data: MatrixByCodeResponseData,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SandboxResponse {
+    pub success: bool,
+    pub data: GameProps,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportResponse {
+    pub success: bool,
+    pub champion: String,
+    pub complete: bool,
+    pub issues: Vec<String>,
 }
@@ -77,6 +77,8 @@ pub struct AllStatsActivePlayer {
     pub champion_stats: AllStatsChampionStats,
     pub base_stats: GameCoreStats,
     pub bonus_stats: GameCoreStats,
+    pub params: TargetReplacements,
+    pub stacks: HashMap<String, u32>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -87,6 +89,7 @@ pub struct AllStatsRealStats {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AllStatsPlayer {
+    pub current_health: f64,
     pub multiplier: AllStatsMultiplier,
     pub real_stats: AllStatsRealStats,
     pub champion_stats: GameCoreStats,
@@ -109,6 +112,9 @@ pub struct TargetAllStats {
     pub active_player: AllStatsActivePlayer,
     pub player: AllStatsPlayer,
     pub property: AllStatsProperty,
+    /** Formula variables, built once so every ability and item reuses them */
+    #[serde(skip)]
+    pub replacements: TargetReplacements,
 }
 
 #[derive(Debug, Deserialize, Serialize)]